@@ -1,55 +1,40 @@
+use advent_of_code::util::first_last_digit;
+
 advent_of_code::solution!(1);
 
+/// Returns the number formed by the first and last digit in the line, or `None` if the line has no digits.
+fn try_calibration_value(line: &str) -> Option<u32> {
+    try_calibration_value_with_words(line, &[])
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
-    input
-        .lines()
-        .map(|line| {
-            let first = line.chars().find_map(|c| c.to_digit(10)).unwrap();
-            let last = line.chars().rev().find_map(|c| c.to_digit(10)).unwrap();
-            first * 10 + last
-        })
-        .sum::<u32>()
-        .into()
+    input.lines().map(try_calibration_value).sum()
+}
+
+/// The English number words, used by part two.
+const ENGLISH_WORDS: &[(&str, u32)] = &[
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+/// Like [`try_calibration_value`], but also recognizes the given `(word, digit)` pairs as digits.
+fn try_calibration_value_with_words(line: &str, words: &[(&str, u32)]) -> Option<u32> {
+    let (first, last) = first_last_digit(line, words)?;
+    Some(first * 10 + last)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
     input
         .lines()
-        .map(|line| {
-            let first = (0..line.len())
-                .find_map(|index| match &line[index..] {
-                    s if s.starts_with('1') || s.starts_with("one") => Some(1),
-                    s if s.starts_with('2') || s.starts_with("two") => Some(2),
-                    s if s.starts_with('3') || s.starts_with("three") => Some(3),
-                    s if s.starts_with('4') || s.starts_with("four") => Some(4),
-                    s if s.starts_with('5') || s.starts_with("five") => Some(5),
-                    s if s.starts_with('6') || s.starts_with("six") => Some(6),
-                    s if s.starts_with('7') || s.starts_with("seven") => Some(7),
-                    s if s.starts_with('8') || s.starts_with("eight") => Some(8),
-                    s if s.starts_with('9') || s.starts_with("nine") => Some(9),
-                    _ => None,
-                })
-                .unwrap();
-            let last = (0..line.len())
-                .rev()
-                .find_map(|index| match &line[..=index] {
-                    s if s.ends_with('1') || s.ends_with("one") => Some(1),
-                    s if s.ends_with('2') || s.ends_with("two") => Some(2),
-                    s if s.ends_with('3') || s.ends_with("three") => Some(3),
-                    s if s.ends_with('4') || s.ends_with("four") => Some(4),
-                    s if s.ends_with('5') || s.ends_with("five") => Some(5),
-                    s if s.ends_with('6') || s.ends_with("six") => Some(6),
-                    s if s.ends_with('7') || s.ends_with("seven") => Some(7),
-                    s if s.ends_with('8') || s.ends_with("eight") => Some(8),
-                    s if s.ends_with('9') || s.ends_with("nine") => Some(9),
-                    _ => None,
-                })
-                .unwrap();
-
-            first * 10 + last
-        })
-        .sum::<u32>()
-        .into()
+        .map(|line| try_calibration_value_with_words(line, ENGLISH_WORDS))
+        .sum()
 }
 
 #[cfg(test)]
@@ -64,6 +49,13 @@ mod tests {
         assert_eq!(result, Some(142));
     }
 
+    #[test]
+    fn test_part_one_without_digits() {
+        assert_eq!(try_calibration_value("a1b2c3d"), Some(13));
+        assert_eq!(try_calibration_value("abcdef"), None);
+        assert_eq!(part_one("1abc2\nabcdef\n"), None);
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file_part(
@@ -71,4 +63,24 @@ mod tests {
         ));
         assert_eq!(result, Some(281));
     }
+
+    #[test]
+    fn test_custom_words() {
+        let words = [("zero", 0), ("eins", 1), ("zwei", 2)];
+        assert_eq!(
+            try_calibration_value_with_words("zero5one", &words),
+            Some(5)
+        );
+        assert_eq!(
+            try_calibration_value_with_words("xeinsyzweiz", &words),
+            Some(12)
+        );
+        assert_eq!(try_calibration_value_with_words("twone", &words), None);
+
+        // The default table doesn't know "zero"
+        assert_eq!(
+            try_calibration_value_with_words("zero5one", ENGLISH_WORDS),
+            Some(51)
+        );
+    }
 }
@@ -40,12 +40,12 @@ fn parse_set(input: &str) -> IResult<&str, [u32; 3]> {
     )(input)
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (_, games) = parse_input(input).unwrap();
+/// The number of red, green and blue cubes in the bag in part one.
+const BAG_CUBES: [u32; 3] = [12, 13, 14];
 
-    let max_red = 12;
-    let max_green = 13;
-    let max_blue = 14;
+/// Sums the ids of the games that are possible with a bag containing `maxima` red, green and blue cubes.
+fn feasible_id_sum(games: &[Vec<[u32; 3]>], maxima: [u32; 3]) -> u32 {
+    let [max_red, max_green, max_blue] = maxima;
 
     games
         .iter()
@@ -56,13 +56,11 @@ pub fn part_one(input: &str) -> Option<u32> {
             })
         })
         .map(|(index, _)| index as u32 + 1)
-        .sum::<u32>()
-        .into()
+        .sum()
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (_, games) = parse_input(input).unwrap();
-
+/// Sums the powers of the minimal sets of cubes that make each game possible.
+fn power_sum(games: &[Vec<[u32; 3]>]) -> u32 {
     games
         .iter()
         .map(|game| {
@@ -79,8 +77,31 @@ pub fn part_two(input: &str) -> Option<u32> {
 
             max_red * max_green * max_blue
         })
-        .sum::<u32>()
-        .into()
+        .sum()
+}
+
+/// Computes both answers from a single parse of the input.
+fn both_parts(input: &str) -> (Option<u32>, Option<u32>) {
+    let (_, games) = parse_input(input).unwrap();
+    (
+        Some(feasible_id_sum(&games, BAG_CUBES)),
+        Some(power_sum(&games)),
+    )
+}
+
+/// Sums the ids of the games in the input that are possible with a bag containing `maxima` red, green and blue cubes.
+#[cfg(test)]
+fn solve_part_one(input: &str, maxima: [u32; 3]) -> Option<u32> {
+    let (_, games) = parse_input(input).unwrap();
+    Some(feasible_id_sum(&games, maxima))
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    both_parts(input).0
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    both_parts(input).1
 }
 
 #[cfg(test)]
@@ -93,9 +114,26 @@ mod tests {
         assert_eq!(result, Some(8));
     }
 
+    #[test]
+    fn test_feasible_id_sum_maxima() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let (_, games) = parse_input(&input).unwrap();
+        assert_eq!(feasible_id_sum(&games, BAG_CUBES), 8);
+
+        // Game 1 shows 6 blue cubes at once, so it's no longer possible with only 5
+        assert_eq!(feasible_id_sum(&games, [12, 13, 5]), 7);
+        assert_eq!(solve_part_one(&input, [12, 13, 5]), Some(7));
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(2286));
     }
+
+    #[test]
+    fn test_both_parts() {
+        let result = both_parts(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, (Some(8), Some(2286)));
+    }
 }
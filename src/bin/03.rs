@@ -1,4 +1,4 @@
-use advent_of_code::util::CharGrid;
+use advent_of_code::util::{read_normalized, CharGrid};
 
 advent_of_code::solution!(3);
 
@@ -7,67 +7,27 @@ fn is_special_char(c: char) -> bool {
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let grid = CharGrid::new(input);
-
-    let mut result = 0;
-
-    for y in 0..grid.height() {
-        let mut num = 0;
-        let mut is_part_num = false;
-
-        let y_prev = if y > 0 { y - 1 } else { y };
-        let y_next = if y + 1 < grid.height() { y + 1 } else { y };
-
-        for x in 0..grid.width() {
-            if let Some(d) = grid.get(x, y).unwrap().to_digit(10) {
-                // Check left neighbors for special char
-                if !is_part_num && num == 0 && x > 0 {
-                    for ny in y_prev..=y_next {
-                        if is_special_char(grid.get(x - 1, ny).unwrap()) {
-                            is_part_num = true;
-                            break;
-                        }
-                    }
-                }
-                // Check top/bottom neighbors for special char
-                if !is_part_num && y_prev != y && is_special_char(grid.get(x, y_prev).unwrap()) {
-                    is_part_num = true;
-                }
-                if !is_part_num && y_next != y && is_special_char(grid.get(x, y_next).unwrap()) {
-                    is_part_num = true;
-                }
-
-                num = num * 10 + d;
-            } else {
-                // Check right neighbors for special char (note that x has already been incremented)
-                if num != 0 && !is_part_num {
-                    for ny in y_prev..=y_next {
-                        if is_special_char(grid.get(x, ny).unwrap()) {
-                            is_part_num = true;
-                            break;
-                        }
-                    }
-                }
-
-                if is_part_num {
-                    result += num;
-                    is_part_num = false;
-                }
-
-                num = 0;
-            }
-        }
-
-        if is_part_num {
-            result += num;
-        }
-    }
+    let input = read_normalized(input);
+    let grid = CharGrid::new(&input);
+
+    let result = grid
+        .scan_numbers()
+        .filter(|&(_, y, x_start, x_end)| {
+            // Check all neighbors of the number for a special char
+            (y.saturating_sub(1)..=y + 1).any(|ny| {
+                (x_start.saturating_sub(1)..=x_end)
+                    .any(|nx| grid.get(nx, ny).is_some_and(is_special_char))
+            })
+        })
+        .map(|(num, ..)| num)
+        .sum();
 
     Some(result)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let grid = CharGrid::new(input);
+    let input = read_normalized(input);
+    let grid = CharGrid::new(&input);
 
     let parse_num_rtl = |x: usize, y: usize| -> u32 {
         let mut num = 0;
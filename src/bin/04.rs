@@ -1,3 +1,4 @@
+use advent_of_code::util::{cascade_counts, LinearIndexer, VecSet};
 use nom::bytes::complete::tag;
 use nom::character::complete::{digit1, space0, space1};
 use nom::combinator::{map_res, value};
@@ -23,11 +24,16 @@ fn iter_wins(input: &str) -> impl Iterator<Item = u32> + '_ {
         // Parse "Card #: " prefix
         let (mut line, _) = parse_prefix(line).unwrap();
 
-        // Parse winning numbers, and store them in a mask
-        let mut winning_mask = [false; 100];
+        // Parse winning numbers, and store them in a set sized to the largest one
+        let mut winning_numbers = vec![];
         while let Ok((next_line, number)) = parse_number(line) {
             line = next_line;
-            winning_mask[number as usize] = true;
+            winning_numbers.push(number);
+        }
+        let max_number = winning_numbers.iter().copied().max().unwrap_or(0);
+        let mut winning_set = VecSet::new(LinearIndexer::new(max_number + 1));
+        for number in winning_numbers {
+            winning_set.insert(number);
         }
 
         // Parse "| " separator
@@ -37,7 +43,7 @@ fn iter_wins(input: &str) -> impl Iterator<Item = u32> + '_ {
         let mut result = 0u32;
         while let Ok((next_line, number)) = parse_number(line) {
             line = next_line;
-            if winning_mask[number as usize] {
+            if number <= max_number && winning_set.contains(&number) {
                 result += 1;
             }
         }
@@ -59,16 +65,9 @@ pub fn part_one(input: &str) -> Option<u32> {
         .into()
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(input: &str) -> Option<u64> {
     let original_card_wins = iter_wins(input).collect::<Vec<_>>();
-
-    let mut num_cards = vec![1; original_card_wins.len()];
-    for i in 0..original_card_wins.len() {
-        for di in 0..original_card_wins[i] {
-            num_cards[i + (di as usize) + 1] += num_cards[i];
-        }
-    }
-
+    let num_cards = cascade_counts(&original_card_wins);
     Some(num_cards.iter().sum())
 }
 
@@ -87,4 +86,32 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(30));
     }
+
+    #[test]
+    fn test_part_two_overflows_u32() {
+        // Card i wins a copy of every later card, so it ends up with 2^i copies
+        let n = 34;
+        let winning = (1..n).map(|x| x.to_string()).collect::<Vec<_>>().join(" ");
+        let input = (0..n)
+            .map(|i| {
+                let numbers = (1..n - i)
+                    .chain([99])
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                format!("Card {}: {} | {}\n", i + 1, winning, numbers)
+            })
+            .collect::<String>();
+
+        let result = part_two(&input);
+        assert_eq!(result, Some((1 << n) - 1));
+        assert!(result.unwrap() > u32::MAX as u64);
+    }
+
+    #[test]
+    fn test_large_numbers() {
+        let input = "Card 1: 150 7 | 150 7 200 3\nCard 2: 12 | 150 120\n";
+        assert_eq!(iter_wins(input).collect::<Vec<_>>(), [2, 0]);
+        assert_eq!(part_one(input), Some(2));
+    }
 }
@@ -1,9 +1,9 @@
 use itertools::Itertools;
 use nom::bytes::complete::tag;
-use nom::character::complete::{alpha1, digit1, line_ending, multispace1, space1};
+use nom::character::complete::{alpha1, digit1, line_ending, multispace0, multispace1, space1};
 use nom::combinator::{map, map_res};
 use nom::multi::separated_list1;
-use nom::sequence::{preceded, separated_pair, tuple};
+use nom::sequence::{preceded, separated_pair, terminated, tuple};
 use nom::IResult;
 advent_of_code::solution!(5);
 
@@ -16,17 +16,29 @@ struct MapEntry {
     range_length: usize,
 }
 
+impl MapEntry {
+    /// Maps `value` to its destination, if it is inside the source range of this entry.
+    fn map(&self, value: usize) -> Option<usize> {
+        let offset = value.checked_sub(self.source_range_start)?;
+        (offset < self.range_length).then_some(self.destination_range_start + offset)
+    }
+}
+
 fn parse_input(input: &str) -> IResult<&str, (Vec<usize>, Vec<Map>)> {
     separated_pair(
-        preceded(
-            tag("seeds: "),
-            separated_list1(space1, map_res(digit1, str::parse)),
-        ),
+        parse_seeds,
         multispace1,
         separated_list1(multispace1, parse_map),
     )(input)
 }
 
+fn parse_seeds(input: &str) -> IResult<&str, Vec<usize>> {
+    preceded(
+        tag("seeds: "),
+        separated_list1(space1, map_res(digit1, str::parse)),
+    )(input)
+}
+
 fn parse_map(input: &str) -> IResult<&str, Map> {
     let (input, _) = tuple((alpha1, tag("-to-"), alpha1, tag(" map:"), line_ending))(input)?;
     separated_list1(
@@ -48,34 +60,47 @@ fn parse_map(input: &str) -> IResult<&str, Map> {
     )(input)
 }
 
+/// Looks up the destination for `value` in a map sorted by `source_range_start`.
+///
+/// Values not covered by any entry map to themselves.
+fn lookup(map: &[MapEntry], value: usize) -> usize {
+    // Index of the first entry that starts after the value
+    let index = map.partition_point(|entry| entry.source_range_start <= value);
+
+    // Only the last entry starting at or before the value can contain it
+    index
+        .checked_sub(1)
+        .and_then(|index| map[index].map(value))
+        .unwrap_or(value)
+}
+
 pub fn part_one(input: &str) -> Option<usize> {
-    let (_, (seeds, mut maps)) = parse_input(input).unwrap();
+    let (mut input, mut values) = terminated(parse_seeds, multispace1)(input).unwrap();
 
-    for map in &mut maps {
+    // Map the seeds through each map as soon as it is parsed, so only one map is in memory at a time
+    while let Ok((rest, mut map)) = terminated(parse_map, multispace0)(input) {
         map.sort_unstable_by_key(|entry| entry.source_range_start);
+        for value in &mut values {
+            *value = lookup(&map, *value);
+        }
+        input = rest;
     }
 
-    seeds
-        .into_iter()
-        .map(|seed| {
-            let mut current = seed;
-            for map in &maps {
-                for entry in map {
-                    let MapEntry {
-                        source_range_start: src_start,
-                        range_length: len,
-                        destination_range_start: dest_start,
-                    } = *entry;
-
-                    if src_start <= current && current < src_start + len {
-                        current = dest_start + (current - src_start);
-                        break;
-                    }
-                }
-            }
-            current
-        })
-        .min()
+    values.into_iter().min()
+}
+
+/// Sorts the `(start, len)` ranges and merges any that overlap or abut, so the number of ranges stays bounded.
+fn coalesce_ranges(ranges: &mut Vec<(usize, usize)>) {
+    ranges.sort_unstable_by_key(|(start, _)| *start);
+    ranges.dedup_by(|(next_start, next_len), (start, len)| {
+        if *next_start <= *start + *len {
+            // Extend the previous range to cover the next one
+            *len = (*next_start + *next_len).max(*start + *len) - *start;
+            true
+        } else {
+            false
+        }
+    });
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
@@ -88,7 +113,7 @@ pub fn part_two(input: &str) -> Option<usize> {
     let mut current_ranges = seeds.into_iter().tuples::<(_, _)>().collect_vec();
 
     for map in maps {
-        current_ranges.sort_unstable_by_key(|(start, _)| *start);
+        coalesce_ranges(&mut current_ranges);
 
         let mut map_entry_index = 0;
         let mut new_ranges = Vec::new();
@@ -167,4 +192,47 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(46));
     }
+
+    fn lookup_linear(map: &[MapEntry], value: usize) -> usize {
+        map.iter()
+            .find_map(|entry| entry.map(value))
+            .unwrap_or(value)
+    }
+
+    #[test]
+    fn test_lookup() {
+        let (_, (_, mut maps)) =
+            parse_input(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        for map in &mut maps {
+            map.sort_unstable_by_key(|entry| entry.source_range_start);
+            for value in 0..120 {
+                assert_eq!(lookup(map, value), lookup_linear(map, value));
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup_many_entries() {
+        // Entries of varying lengths with gaps in between, mapped to a shuffled destination
+        let mut map = (0..500)
+            .map(|i| MapEntry {
+                destination_range_start: 1_000_000 + (i * 7919) % 500 * 100,
+                source_range_start: i * 20 + i % 3,
+                range_length: 5 + i % 11,
+            })
+            .collect_vec();
+        map.reverse();
+        map.sort_unstable_by_key(|entry| entry.source_range_start);
+
+        for value in 0..10_100 {
+            assert_eq!(lookup(&map, value), lookup_linear(&map, value));
+        }
+    }
+
+    #[test]
+    fn test_coalesce_ranges() {
+        let mut ranges = vec![(30, 2), (15, 3), (10, 5), (31, 4)];
+        coalesce_ranges(&mut ranges);
+        assert_eq!(ranges, vec![(10, 8), (30, 5)]);
+    }
 }
@@ -1,26 +1,32 @@
+use std::fmt::Display;
+
 use itertools::Itertools;
 
 advent_of_code::solution!(7);
 
+/// The card (if any) that acts as a wildcard. A wildcard counts as whichever card makes the hand type strongest, but
+/// is the weakest card when comparing individual cards.
 #[derive(Copy, Clone, Eq, PartialEq)]
-enum JCardType {
-    Jack,
-    Joker,
+struct Wildcard(Option<char>);
+
+impl Wildcard {
+    /// No wildcard, `J` is a regular Jack.
+    const JACK: Self = Self(None);
+    /// `J` is a Joker.
+    const JOKER: Self = Self(Some('J'));
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Ord, PartialOrd)]
 struct Card(u32);
 
 impl Card {
-    fn new(c: char, j_card_type: JCardType) -> Self {
+    fn new(c: char, wildcard: Wildcard) -> Self {
         Self(match c {
+            _ if wildcard.0 == Some(c) => 1,
             'A' => 14,
             'K' => 13,
             'Q' => 12,
-            'J' => match j_card_type {
-                JCardType::Jack => 11,
-                JCardType::Joker => 1,
-            },
+            'J' => 11,
             'T' => 10,
             '9' => 9,
             '8' => 8,
@@ -34,9 +40,21 @@ impl Card {
         })
     }
 
-    fn is_joker(&self) -> bool {
+    fn is_wildcard(&self) -> bool {
         self.0 == 1
     }
+
+    fn to_char(self) -> char {
+        match self.0 {
+            14 => 'A',
+            13 => 'K',
+            12 => 'Q',
+            11 => 'J',
+            10 => 'T',
+            1 => '*',
+            value => char::from_digit(value, 10).unwrap(),
+        }
+    }
 }
 
 #[derive(Ord, PartialOrd, Eq, PartialEq, Debug)]
@@ -62,13 +80,38 @@ impl HandType {
             HandType::HighCard => 0,
         }
     }
+
+    fn from_value(value: u32) -> Self {
+        match value {
+            6 => HandType::FiveOfAKind,
+            5 => HandType::FourOfAKind,
+            4 => HandType::FullHouse,
+            3 => HandType::ThreeOfAKind,
+            2 => HandType::TwoPairs,
+            1 => HandType::OnePair,
+            0 => HandType::HighCard,
+            _ => unreachable!(),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            HandType::FiveOfAKind => "Five of a kind",
+            HandType::FourOfAKind => "Four of a kind",
+            HandType::FullHouse => "Full house",
+            HandType::ThreeOfAKind => "Three of a kind",
+            HandType::TwoPairs => "Two pairs",
+            HandType::OnePair => "One pair",
+            HandType::HighCard => "High card",
+        }
+    }
 }
 
 #[derive(Eq, PartialEq, Debug, Ord, PartialOrd)]
 struct Hand(u32);
 
 impl Hand {
-    fn new(cards: [Card; 5], j_card_type: JCardType) -> Self {
+    fn new(cards: [Card; 5], wildcard: Wildcard) -> Self {
         let sorted_cards = {
             let mut cards = cards;
             cards.sort_unstable();
@@ -93,15 +136,15 @@ impl Hand {
             HandType::HighCard
         };
 
-        let hand_type = match j_card_type {
-            JCardType::Jack => hand_type,
-            JCardType::Joker => {
-                let num_jokers = sorted_cards
+        let hand_type = match wildcard.0 {
+            None => hand_type,
+            Some(_) => {
+                let num_wildcards = sorted_cards
                     .into_iter()
-                    .filter(|card| card.is_joker())
+                    .filter(|card| card.is_wildcard())
                     .count();
 
-                match (hand_type, num_jokers) {
+                match (hand_type, num_wildcards) {
                     (HandType::FiveOfAKind, _) => HandType::FiveOfAKind,
                     (HandType::FourOfAKind, 1) | (HandType::FourOfAKind, 4) => {
                         HandType::FiveOfAKind
@@ -136,6 +179,23 @@ impl Hand {
 
         Self(repr)
     }
+
+    fn hand_type(&self) -> HandType {
+        HandType::from_value(self.0 >> 20)
+    }
+
+    fn cards(&self) -> [Card; 5] {
+        std::array::from_fn(|i| Card((self.0 >> (4 * (4 - i))) & 0xF))
+    }
+}
+
+impl Display for Hand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for card in self.cards() {
+            write!(f, "{}", card.to_char())?;
+        }
+        write!(f, " ({})", self.hand_type().name())
+    }
 }
 
 fn parse_input_iter(input: &str) -> impl Iterator<Item = ([char; 5], u32)> + '_ {
@@ -147,11 +207,11 @@ fn parse_input_iter(input: &str) -> impl Iterator<Item = ([char; 5], u32)> + '_
     })
 }
 
-fn solve(input: &str, j_card_type: JCardType) -> Option<u32> {
+fn solve(input: &str, wildcard: Wildcard) -> Option<u32> {
     let mut hands = parse_input_iter(input)
         .map(|(cards, bid)| {
-            let cards = cards.map(|c| Card::new(c, j_card_type));
-            (Hand::new(cards, j_card_type), bid)
+            let cards = cards.map(|c| Card::new(c, wildcard));
+            (Hand::new(cards, wildcard), bid)
         })
         .collect::<Vec<_>>();
 
@@ -165,11 +225,11 @@ fn solve(input: &str, j_card_type: JCardType) -> Option<u32> {
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    solve(input, JCardType::Jack)
+    solve(input, Wildcard::JACK)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    solve(input, JCardType::Joker)
+    solve(input, Wildcard::JOKER)
 }
 
 #[cfg(test)]
@@ -187,4 +247,25 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(5905));
     }
+
+    #[test]
+    fn test_display_hand() {
+        let cards = ['T', 'T', 'K', 'K', 'T'].map(|c| Card::new(c, Wildcard::JACK));
+        let hand = Hand::new(cards, Wildcard::JACK);
+        assert_eq!(hand.to_string(), "TTKKT (Full house)");
+    }
+
+    #[test]
+    fn test_other_wildcard() {
+        let hand = |wildcard| {
+            let cards = ['A', '2', 'K', 'K', '3'].map(|c| Card::new(c, wildcard));
+            Hand::new(cards, wildcard)
+        };
+
+        assert_eq!(hand(Wildcard::JOKER).hand_type(), HandType::OnePair);
+
+        let hand = hand(Wildcard(Some('A')));
+        assert_eq!(hand.hand_type(), HandType::ThreeOfAKind);
+        assert_eq!(hand.to_string(), "*2KK3 (Three of a kind)");
+    }
 }
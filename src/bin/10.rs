@@ -1,26 +1,11 @@
-use itertools::Itertools;
-advent_of_code::solution!(10);
+use std::fmt::{Display, Formatter};
 
-#[derive(Eq, PartialEq, Copy, Clone, Debug)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
+use itertools::Itertools;
 
-impl Direction {
-    const VALUES: [Self; 4] = [Self::North, Self::South, Self::East, Self::West];
+use advent_of_code::util::coord::{Coord, CoordIndexer, Direction};
+use advent_of_code::util::count_interior_cells;
 
-    fn opposite(&self) -> Self {
-        match self {
-            Self::North => Self::South,
-            Self::South => Self::North,
-            Self::East => Self::West,
-            Self::West => Self::East,
-        }
-    }
-}
+advent_of_code::solution!(10);
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 enum Tile {
@@ -49,21 +34,72 @@ impl Tile {
         }
     }
 
+    fn to_char(self) -> char {
+        match self {
+            Self::VerticalPipe => '|',
+            Self::HorizontalPipe => '-',
+            Self::NorthEastPipe => 'L',
+            Self::NorthWestPipe => 'J',
+            Self::SouthWestPipe => '7',
+            Self::SouthEastPipe => 'F',
+            Self::Ground => '.',
+            Self::Start => 'S',
+        }
+    }
+
+    fn to_box_char(self) -> char {
+        match self {
+            Self::VerticalPipe => '│',
+            Self::HorizontalPipe => '─',
+            Self::NorthEastPipe => '└',
+            Self::NorthWestPipe => '┘',
+            Self::SouthWestPipe => '┐',
+            Self::SouthEastPipe => '┌',
+            Self::Ground => ' ',
+            Self::Start => 'S',
+        }
+    }
+
+    /// Returns the pipe connecting the two given directions, in either order.
+    fn from_directions(a: Direction, b: Direction) -> Option<Self> {
+        match (a, b) {
+            (Direction::Up, Direction::Down) | (Direction::Down, Direction::Up) => {
+                Some(Self::VerticalPipe)
+            }
+            (Direction::Right, Direction::Left) | (Direction::Left, Direction::Right) => {
+                Some(Self::HorizontalPipe)
+            }
+            (Direction::Up, Direction::Right) | (Direction::Right, Direction::Up) => {
+                Some(Self::NorthEastPipe)
+            }
+            (Direction::Up, Direction::Left) | (Direction::Left, Direction::Up) => {
+                Some(Self::NorthWestPipe)
+            }
+            (Direction::Down, Direction::Right) | (Direction::Right, Direction::Down) => {
+                Some(Self::SouthEastPipe)
+            }
+            (Direction::Down, Direction::Left) | (Direction::Left, Direction::Down) => {
+                Some(Self::SouthWestPipe)
+            }
+            _ => None,
+        }
+    }
+
     /// Maps from incoming direction to outgoing direction
     fn step_from(&self, incoming_direction: Direction) -> Option<Direction> {
         match (self, incoming_direction) {
-            (Self::VerticalPipe, Direction::North) => Some(Direction::North),
-            (Self::VerticalPipe, Direction::South) => Some(Direction::South),
-            (Self::HorizontalPipe, Direction::East) => Some(Direction::East),
-            (Self::HorizontalPipe, Direction::West) => Some(Direction::West),
-            (Self::NorthEastPipe, Direction::South) => Some(Direction::East),
-            (Self::NorthEastPipe, Direction::West) => Some(Direction::North),
-            (Self::NorthWestPipe, Direction::South) => Some(Direction::West),
-            (Self::NorthWestPipe, Direction::East) => Some(Direction::North),
-            (Self::SouthEastPipe, Direction::North) => Some(Direction::East),
-            (Self::SouthEastPipe, Direction::West) => Some(Direction::South),
-            (Self::SouthWestPipe, Direction::North) => Some(Direction::West),
-            (Self::SouthWestPipe, Direction::East) => Some(Direction::South),
+            (Self::VerticalPipe, Direction::Up) => Some(Direction::Up),
+            (Self::VerticalPipe, Direction::Down) => Some(Direction::Down),
+            (Self::HorizontalPipe, Direction::Right) => Some(Direction::Right),
+            (Self::HorizontalPipe, Direction::Left) => Some(Direction::Left),
+            (Self::NorthEastPipe, Direction::Down) => Some(Direction::Right),
+            (Self::NorthEastPipe, Direction::Left) => Some(Direction::Up),
+            (Self::NorthWestPipe, Direction::Down) => Some(Direction::Left),
+            (Self::NorthWestPipe, Direction::Right) => Some(Direction::Up),
+            (Self::SouthEastPipe, Direction::Up) => Some(Direction::Right),
+            (Self::SouthEastPipe, Direction::Left) => Some(Direction::Down),
+            (Self::SouthWestPipe, Direction::Up) => Some(Direction::Left),
+            (Self::SouthWestPipe, Direction::Right) => Some(Direction::Down),
             (_, _) => None,
         }
     }
@@ -71,7 +107,8 @@ impl Tile {
 
 struct Map {
     tiles: Vec<Vec<Tile>>,
-    start: (usize, usize),
+    indexer: CoordIndexer,
+    start: Coord,
 }
 
 impl Map {
@@ -84,158 +121,127 @@ impl Map {
                 if let Some(tile) = Tile::from_char(c) {
                     row.push(tile);
                     if tile == Tile::Start {
-                        start = Some((x, y));
+                        start = Some(Coord::new(x, y));
                     }
                 }
             }
             tiles.push(row);
         }
+        let indexer = CoordIndexer::new(tiles[0].len(), tiles.len());
         Self {
             tiles,
+            indexer,
             start: start.unwrap(),
         }
     }
+
+    fn tile(&self, coord: Coord) -> Tile {
+        self.tiles[coord.y][coord.x]
+    }
+
+    fn tile_mut(&mut self, coord: Coord) -> &mut Tile {
+        &mut self.tiles[coord.y][coord.x]
+    }
+}
+
+impl Map {
+    /// Renders the tiles on the loop using box-drawing characters, and all other tiles as `.`.
+    fn display_loop<'a>(&'a self, is_tile_on_loop: &'a [Vec<bool>]) -> impl Display + 'a {
+        LoopOverlay {
+            map: self,
+            is_tile_on_loop,
+        }
+    }
+}
+
+impl Display for Map {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in &self.tiles {
+            for tile in row {
+                write!(f, "{}", tile.to_char())?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+struct LoopOverlay<'a> {
+    map: &'a Map,
+    is_tile_on_loop: &'a [Vec<bool>],
+}
+
+impl Display for LoopOverlay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (row, is_on_loop_row) in self.map.tiles.iter().zip(self.is_tile_on_loop) {
+            for (tile, is_on_loop) in row.iter().zip(is_on_loop_row) {
+                let c = if *is_on_loop { tile.to_box_char() } else { '.' };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
 }
 
 fn both_parts(input: &str) -> (Option<u32>, Option<u32>) {
     let mut map = Map::from_str(input);
 
-    let (mut x, mut y) = map.start;
+    let mut coord = map.start;
 
     // Find valid initial direction
-    let start_directions = Direction::VALUES
-        .into_iter()
-        .filter(|direction| {
-            let (nx, ny) = match direction {
-                Direction::North => {
-                    if y == 0 {
-                        return false;
-                    }
-                    (x, y - 1)
-                }
-                Direction::South => {
-                    if y == map.tiles.len() - 1 {
-                        return false;
-                    }
-                    (x, y + 1)
-                }
-                Direction::East => {
-                    if x == map.tiles[y].len() - 1 {
-                        return false;
-                    }
-                    (x + 1, y)
-                }
-                Direction::West => {
-                    if x == 0 {
-                        return false;
-                    }
-                    (x - 1, y)
-                }
-            };
-
-            map.tiles[ny][nx].step_from(*direction).is_some()
+    let start_directions = Direction::all()
+        .filter(|&direction| {
+            map.indexer
+                .step(coord, direction)
+                .is_some_and(|next_coord| map.tile(next_coord).step_from(direction).is_some())
         })
         .collect_tuple::<(_, _)>()
         .unwrap();
 
     // Replace start tile with pipe
-    match start_directions {
-        (Direction::North, Direction::South) => {
-            map.tiles[y][x] = Tile::VerticalPipe;
-        }
-        (Direction::East, Direction::West) => {
-            map.tiles[y][x] = Tile::HorizontalPipe;
-        }
-        (Direction::North, Direction::East) => {
-            map.tiles[y][x] = Tile::NorthEastPipe;
-        }
-        (Direction::North, Direction::West) => {
-            map.tiles[y][x] = Tile::NorthWestPipe;
-        }
-        (Direction::South, Direction::East) => {
-            map.tiles[y][x] = Tile::SouthEastPipe;
-        }
-        (Direction::South, Direction::West) => {
-            map.tiles[y][x] = Tile::SouthWestPipe;
-        }
-        _ => unreachable!("Invalid start"),
-    }
+    *map.tile_mut(coord) =
+        Tile::from_directions(start_directions.0, start_directions.1).expect("Invalid start");
 
     let map = map;
     let mut direction = start_directions.0;
 
-    let mut is_tile_on_loop = vec![vec![false; map.tiles[0].len()]; map.tiles.len()];
+    let mut is_tile_on_loop = vec![vec![false; map.indexer.width]; map.indexer.height];
     let mut steps = 0;
     loop {
-        is_tile_on_loop[y][x] = true;
-
-        (x, y) = match direction {
-            Direction::North => (x, y - 1),
-            Direction::South => (x, y + 1),
-            Direction::East => (x + 1, y),
-            Direction::West => (x - 1, y),
-        };
+        is_tile_on_loop[coord.y][coord.x] = true;
+
+        coord = map.indexer.step(coord, direction).unwrap();
         steps += 1;
 
-        if x == map.start.0 && y == map.start.1 {
+        if coord == map.start {
             break;
         }
 
-        match map.tiles[y][x].step_from(direction) {
+        match map.tile(coord).step_from(direction) {
             Some(new_direction) => direction = new_direction,
             None => unreachable!("Invalid loop"),
         }
     }
 
-    let mut covered_tiles = 0;
-
-    for (y, row) in map.tiles.iter().enumerate() {
-        let mut is_within_loop = false;
-        let mut x = 0;
-
-        while x < row.len() {
-            if !is_tile_on_loop[y][x] {
-                if is_within_loop {
-                    covered_tiles += 1;
-                }
-                x += 1;
-                continue;
-            }
-
-            let tile = row[x];
-            match tile {
-                Tile::VerticalPipe => {
-                    covered_tiles += 1;
-                    is_within_loop = !is_within_loop;
-                }
-                Tile::NorthEastPipe | Tile::SouthEastPipe => {
-                    covered_tiles += 1;
-
-                    // Walk east until we hit the next corner
-                    while row[x] != Tile::NorthWestPipe && row[x] != Tile::SouthWestPipe {
-                        covered_tiles += 1;
-                        x += 1;
-                    }
-
-                    match (tile, row[x]) {
-                        (Tile::NorthEastPipe, Tile::SouthWestPipe) => {
-                            is_within_loop = !is_within_loop;
-                        }
-                        (Tile::SouthEastPipe, Tile::NorthWestPipe) => {
-                            is_within_loop = !is_within_loop;
-                        }
-                        _ => {}
-                    }
-                }
-                _ => unreachable!(),
-            }
-
-            x += 1;
-        }
+    if cfg!(feature = "debug_output") {
+        println!("{}", map.display_loop(&is_tile_on_loop));
     }
 
+    let interior_tiles = count_interior_cells(
+        map.indexer.width,
+        map.indexer.height,
+        |coord| is_tile_on_loop[coord.y][coord.x],
+        |coord| {
+            matches!(
+                map.tile(coord),
+                Tile::VerticalPipe | Tile::NorthEastPipe | Tile::NorthWestPipe
+            )
+        },
+    );
+
     let part_one = steps / 2;
-    let part_two = covered_tiles - steps;
+    let part_two = interior_tiles as u32;
 
     (Some(part_one), Some(part_two))
 }
@@ -282,4 +288,29 @@ mod tests {
         ));
         assert_eq!(result, Some(10));
     }
+
+    #[test]
+    fn test_display() {
+        for part in 1..=5 {
+            let input = advent_of_code::template::read_file_part("examples", DAY, part);
+            assert_eq!(Map::from_str(&input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_tile_from_directions() {
+        let cases = [
+            (Direction::Up, Direction::Down, Tile::VerticalPipe),
+            (Direction::Right, Direction::Left, Tile::HorizontalPipe),
+            (Direction::Up, Direction::Right, Tile::NorthEastPipe),
+            (Direction::Up, Direction::Left, Tile::NorthWestPipe),
+            (Direction::Down, Direction::Right, Tile::SouthEastPipe),
+            (Direction::Down, Direction::Left, Tile::SouthWestPipe),
+        ];
+        for (a, b, tile) in cases {
+            assert_eq!(Tile::from_directions(a, b), Some(tile));
+            assert_eq!(Tile::from_directions(b, a), Some(tile));
+        }
+        assert_eq!(Tile::from_directions(Direction::Up, Direction::Up), None);
+    }
 }
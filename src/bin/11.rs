@@ -2,51 +2,66 @@ use itertools::Itertools;
 
 advent_of_code::solution!(11);
 
-fn solve(input: &str, expansion_factor: usize) -> Option<usize> {
+/// Sums the distances between all pairs of galaxies, after expanding empty rows and columns by `expansion_factor`.
+///
+/// Coordinates and distances are accumulated as `u64` regardless of the target's pointer width. For `n` galaxies in
+/// a `d`-by-`d` grid, the sum is at most `n^2 * d * expansion_factor`, which for a 140-by-140 input with ~450 galaxies
+/// and an expansion factor of one million is about `3 * 10^16`, well below `u64::MAX`.
+///
+/// Returns `None` if there are no pairs of galaxies, or if the expanded coordinates or the sum overflow `u64`.
+fn solve(input: &str, expansion_factor: u64) -> Option<u64> {
     let mut galaxies = vec![];
     for (y, line) in input.lines().enumerate() {
         for (x, c) in line.chars().enumerate() {
             if c == '#' {
-                galaxies.push((x, y));
+                galaxies.push((x as u64, y as u64));
             }
         }
     }
 
     // Vertical expansion
     let mut y = 0;
-    let mut y_expansion = 0;
+    let mut y_expansion = 0u64;
     for galaxy in galaxies.iter_mut() {
         if galaxy.1 > y + 1 {
-            y_expansion += (galaxy.1 - y - 1) * (expansion_factor - 1);
+            let expansion = (galaxy.1 - y - 1).checked_mul(expansion_factor - 1)?;
+            y_expansion = y_expansion.checked_add(expansion)?;
         }
         y = galaxy.1;
-        galaxy.1 += y_expansion;
+        galaxy.1 = galaxy.1.checked_add(y_expansion)?;
     }
 
     // Horizontal expansion
     let mut x = 0;
-    let mut x_expansion = 0;
+    let mut x_expansion = 0u64;
     galaxies.sort_by_key(|(x, _)| *x);
     for galaxy in galaxies.iter_mut() {
         if galaxy.0 > x + 1 {
-            x_expansion += (galaxy.0 - x - 1) * (expansion_factor - 1);
+            let expansion = (galaxy.0 - x - 1).checked_mul(expansion_factor - 1)?;
+            x_expansion = x_expansion.checked_add(expansion)?;
         }
         x = galaxy.0;
-        galaxy.0 += x_expansion;
+        galaxy.0 = galaxy.0.checked_add(x_expansion)?;
     }
 
-    galaxies
-        .iter()
-        .tuple_combinations()
-        .map(|((x1, y1), (x2, y2))| x1.abs_diff(*x2) + y1.abs_diff(*y2))
-        .sum1()
+    let mut num_pairs = 0;
+    let sum =
+        galaxies
+            .iter()
+            .tuple_combinations()
+            .try_fold(0u64, |sum, ((x1, y1), (x2, y2))| {
+                num_pairs += 1;
+                sum.checked_add(x1.abs_diff(*x2).checked_add(y1.abs_diff(*y2))?)
+            })?;
+
+    (num_pairs > 0).then_some(sum)
 }
 
-pub fn part_one(input: &str) -> Option<usize> {
+pub fn part_one(input: &str) -> Option<u64> {
     solve(input, 2)
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
+pub fn part_two(input: &str) -> Option<u64> {
     solve(input, 1_000_000)
 }
 
@@ -68,4 +83,24 @@ mod tests {
         let result = solve(&advent_of_code::template::read_file("examples", DAY), 100);
         assert_eq!(result, Some(8410));
     }
+
+    #[test]
+    fn test_large_expansion_factor() {
+        let result = solve(
+            &advent_of_code::template::read_file("examples", DAY),
+            1_000_000_000,
+        );
+        assert_eq!(result, Some(82_000_000_210));
+    }
+
+    #[test]
+    fn test_overflowing_expansion_factor() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+
+        // Each empty row/column alone fits, but the sum of the distances doesn't
+        assert_eq!(solve(&input, u64::MAX / 50), None);
+
+        // The expanded coordinates themselves overflow
+        assert_eq!(solve(&input, u64::MAX / 2), None);
+    }
 }
@@ -111,7 +111,235 @@ fn count_arrangements(springs: &[SpringCondition], damaged_groups: &[usize]) ->
     cache_row[springs.len()]
 }
 
+/// Variant of [`count_arrangements`] that recursively counts the arrangements of `springs[spring_index..]` with
+/// `damaged_groups[group_index..]`, memoizing the counts in a table keyed by `(spring_index, group_index)`.
+///
+/// This is slower, but easier to follow, so it serves as a reference implementation for the other variants.
+#[cfg(test)]
+fn count_arrangements_memoized(springs: &[SpringCondition], damaged_groups: &[usize]) -> usize {
+    use advent_of_code::util::coord::{Coord, CoordIndexer};
+    use advent_of_code::util::VecTable;
+
+    type Memo = VecTable<Coord, Option<usize>, CoordIndexer>;
+
+    fn count(
+        springs: &[SpringCondition],
+        damaged_groups: &[usize],
+        spring_index: usize,
+        group_index: usize,
+        memo: &mut Memo,
+    ) -> usize {
+        if spring_index >= springs.len() {
+            return (group_index == damaged_groups.len()) as usize;
+        }
+
+        let key = Coord::new(spring_index, group_index);
+        if let Some(num_arrangements) = *memo.get(&key) {
+            return num_arrangements;
+        }
+
+        let mut num_arrangements = 0;
+
+        if springs[spring_index] != SpringCondition::Damaged {
+            // Spring is operational, continue with the next spring.
+            num_arrangements += count(springs, damaged_groups, spring_index + 1, group_index, memo);
+        }
+
+        if springs[spring_index] != SpringCondition::Operational
+            && group_index < damaged_groups.len()
+        {
+            // Spring starts a damaged group, which must fit and be followed by an operational spring (or the end).
+            let group_end = spring_index + damaged_groups[group_index];
+            let fits = group_end <= springs.len()
+                && springs[spring_index..group_end]
+                    .iter()
+                    .all(|&spring| spring != SpringCondition::Operational)
+                && springs.get(group_end) != Some(&SpringCondition::Damaged);
+            if fits {
+                num_arrangements += count(
+                    springs,
+                    damaged_groups,
+                    group_end + 1,
+                    group_index + 1,
+                    memo,
+                );
+            }
+        }
+
+        memo[key] = Some(num_arrangements);
+        num_arrangements
+    }
+
+    let indexer = CoordIndexer::new(springs.len(), damaged_groups.len() + 1);
+    count(springs, damaged_groups, 0, 0, &mut Memo::new(indexer))
+}
+
+/// A row of springs packed into bitmasks, where bit `i` corresponds to spring `i`. Only rows of up to 128 springs fit.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+struct PackedRow {
+    len: usize,
+    damaged_mask: u128,
+    unknown_mask: u128,
+}
+
+impl PackedRow {
+    fn from_springs(springs: &[SpringCondition]) -> Option<Self> {
+        if springs.len() > 128 {
+            return None;
+        }
+
+        let mut damaged_mask = 0;
+        let mut unknown_mask = 0;
+        for (i, &spring) in springs.iter().enumerate() {
+            match spring {
+                SpringCondition::Operational => {}
+                SpringCondition::Damaged => damaged_mask |= 1 << i,
+                SpringCondition::Unknown => unknown_mask |= 1 << i,
+            }
+        }
+
+        Some(Self {
+            len: springs.len(),
+            damaged_mask,
+            unknown_mask,
+        })
+    }
+
+    fn is_damaged(&self, i: usize) -> bool {
+        self.damaged_mask >> i & 1 == 1
+    }
+
+    /// Whether springs `start..end` could all be damaged.
+    fn can_be_damaged(&self, start: usize, end: usize) -> bool {
+        let len = end - start;
+        let range_mask = if len >= 128 {
+            u128::MAX
+        } else {
+            (1 << len) - 1
+        };
+        !(self.damaged_mask | self.unknown_mask) >> start & range_mask == 0
+    }
+}
+
+/// Variant of [`count_arrangements`] that operates on a [`PackedRow`], checking whether a damaged group can end at a
+/// position with a single mask operation instead of tracking the damaged suffix.
+fn count_arrangements_packed(row: &PackedRow, damaged_groups: &[usize]) -> usize {
+    let mut cache_row = vec![0; row.len + 1];
+    let mut prev_cache_row = vec![0; row.len + 1];
+
+    // Initialize base case and first row: any prefix without damaged springs has exactly one arrangement.
+    let first_damaged = row.damaged_mask.trailing_zeros() as usize;
+    cache_row[..=first_damaged.min(row.len)].fill(1);
+
+    for &damaged_group_len in damaged_groups {
+        std::mem::swap(&mut cache_row, &mut prev_cache_row);
+
+        cache_row[0] = 0;
+        for i in 0..row.len {
+            let mut num_arrangements = 0;
+
+            if !row.is_damaged(i) {
+                num_arrangements += cache_row[i];
+            }
+
+            // A damaged group can end here if its springs can all be damaged, and it is not preceded by a damaged
+            // spring.
+            if damaged_group_len != 0 && damaged_group_len <= i + 1 {
+                let group_start = i + 1 - damaged_group_len;
+                if row.can_be_damaged(group_start, i + 1)
+                    && (group_start == 0 || !row.is_damaged(group_start - 1))
+                {
+                    // Skip the operational spring separating this group from the previous one, if any.
+                    num_arrangements += prev_cache_row[group_start.saturating_sub(1)];
+                }
+            }
+
+            cache_row[i + 1] = num_arrangements;
+        }
+    }
+
+    cache_row[row.len]
+}
+
+/// Variant of [`count_arrangements`] that finds the positions where each damaged group can end using `u64` bitmasks,
+/// checking all positions in a row at once. Falls back to [`count_arrangements_packed`] for rows longer than 64
+/// springs, and to [`count_arrangements`] for rows longer than 128 springs.
+fn count_arrangements_bit_parallel(springs: &[SpringCondition], damaged_groups: &[usize]) -> usize {
+    // The groups and the operational springs between them don't fit, which also keeps the shifts below in range.
+    let min_len = damaged_groups.iter().sum::<usize>() + damaged_groups.len().saturating_sub(1);
+    if min_len > springs.len() {
+        return 0;
+    }
+
+    if springs.len() > 64 {
+        return match PackedRow::from_springs(springs) {
+            Some(row) => count_arrangements_packed(&row, damaged_groups),
+            None => count_arrangements(springs, damaged_groups),
+        };
+    }
+
+    // Bit i is set if spring i has the given condition.
+    let mut operational = 0u64;
+    let mut damaged = 0u64;
+    for (i, &spring) in springs.iter().enumerate() {
+        match spring {
+            SpringCondition::Operational => operational |= 1 << i,
+            SpringCondition::Damaged => damaged |= 1 << i,
+            SpringCondition::Unknown => {}
+        }
+    }
+    let not_operational = !operational;
+
+    let mut cache_row = vec![0; springs.len() + 1];
+    let mut prev_cache_row = vec![0; springs.len() + 1];
+
+    // Initialize base case and first row: any prefix without damaged springs has exactly one arrangement.
+    let first_damaged = damaged.trailing_zeros() as usize;
+    cache_row[..=first_damaged.min(springs.len())].fill(1);
+
+    for &damaged_group_len in damaged_groups {
+        std::mem::swap(&mut cache_row, &mut prev_cache_row);
+
+        // Bit i is set if a damaged group can end at spring i: the group's springs can all be damaged, and the group
+        // is not preceded by a damaged spring.
+        let mut group_ends = not_operational;
+        for k in 1..damaged_group_len {
+            group_ends &= not_operational << k;
+        }
+        if damaged_group_len < 64 {
+            group_ends &= !(damaged << damaged_group_len);
+        }
+        if damaged_group_len == 0 {
+            group_ends = 0;
+        }
+
+        cache_row[0] = 0;
+        for (i, &spring) in springs.iter().enumerate() {
+            let mut num_arrangements = 0;
+
+            if spring != SpringCondition::Damaged {
+                num_arrangements += cache_row[i];
+            }
+
+            if group_ends >> i & 1 == 1 {
+                // Skip the operational spring separating this group from the previous one, if any.
+                num_arrangements += prev_cache_row[(i + 1 - damaged_group_len).saturating_sub(1)];
+            }
+
+            cache_row[i + 1] = num_arrangements;
+        }
+    }
+
+    cache_row[springs.len()]
+}
+
 fn solve(input: &str, repeat: usize) -> Option<usize> {
+    let count_arrangements = if cfg!(feature = "bit_parallel") {
+        count_arrangements_bit_parallel
+    } else {
+        count_arrangements
+    };
+
     input
         .lines()
         .map(|line| parse_line(line, repeat).unwrap().1)
@@ -130,6 +358,7 @@ pub fn part_two(input: &str) -> Option<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::util::test::seeded_random;
 
     #[test]
     fn test_part_one() {
@@ -142,4 +371,95 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(525152));
     }
+
+    #[test]
+    fn test_count_arrangements_bit_parallel() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let edge_cases = [
+            "? 1",
+            "# 1",
+            ". 1",
+            "?? 2",
+            "#?#?#? 1,1,1",
+            "?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#?#? 1,3,1,6,2",
+            "???????????????????????????????????????????????????????????????? 3,2,1",
+            "?????????????????????????????????????????????????????????????????? 3,2,1",
+            "?? 70",
+            "?? 1,1",
+            "??????????????????????????????????????????????????????????????????? 70",
+        ];
+
+        let cases = input
+            .lines()
+            .flat_map(|line| [(line, 1), (line, 5)])
+            .chain(edge_cases.into_iter().map(|line| (line, 1)));
+
+        for (line, repeat) in cases {
+            let (_, (springs, damaged_groups)) = parse_line(line, repeat).unwrap();
+            assert_eq!(
+                count_arrangements_bit_parallel(&springs, &damaged_groups),
+                count_arrangements(&springs, &damaged_groups),
+                "{line} (repeat {repeat})"
+            );
+        }
+    }
+
+    #[test]
+    fn test_count_arrangements_memoized() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let mut lines = input
+            .lines()
+            .flat_map(|line| [(line.to_string(), 1), (line.to_string(), 5)])
+            .collect_vec();
+
+        // Pseudo-random records
+        let mut next_random = seeded_random();
+        for _ in 0..100 {
+            let springs = (0..1 + next_random(20))
+                .map(|_| ['.', '#', '?'][next_random(3)])
+                .collect::<String>();
+            let groups = (0..1 + next_random(4))
+                .map(|_| (1 + next_random(4)).to_string())
+                .join(",");
+            lines.push((format!("{springs} {groups}"), 1));
+        }
+
+        for (line, repeat) in lines {
+            let (_, (springs, damaged_groups)) = parse_line(&line, repeat).unwrap();
+            assert_eq!(
+                count_arrangements_memoized(&springs, &damaged_groups),
+                count_arrangements(&springs, &damaged_groups),
+                "{line} (repeat {repeat})"
+            );
+        }
+    }
+
+    #[test]
+    fn test_count_arrangements_packed() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let edge_cases = [
+            "? 1",
+            "# 1",
+            "#?#?#? 1,1,1",
+            "???????????????????????????????????????????????????????????????????????????????????????????????????????????????????????????????? 128",
+        ];
+
+        let cases = input
+            .lines()
+            .flat_map(|line| [(line, 1), (line, 5)])
+            .chain(edge_cases.into_iter().map(|line| (line, 1)));
+
+        for (line, repeat) in cases {
+            let (_, (springs, damaged_groups)) = parse_line(line, repeat).unwrap();
+            let row = PackedRow::from_springs(&springs).unwrap();
+            assert_eq!(
+                count_arrangements_packed(&row, &damaged_groups),
+                count_arrangements(&springs, &damaged_groups),
+                "{line} (repeat {repeat})"
+            );
+        }
+
+        let springs = [SpringCondition::Unknown; 129];
+        assert_eq!(PackedRow::from_springs(&springs), None);
+    }
 }
@@ -4,9 +4,14 @@ use advent_of_code::util::BitMatrix;
 
 advent_of_code::solution!(13);
 
+/// Size of the pattern matrix in bytes per row, so patterns can be up to `8 * PATTERN_BYTES` rows and columns.
+const PATTERN_BYTES: usize = 4;
+
+type PatternMatrix = BitMatrix<PATTERN_BYTES>;
+
 fn parse_input_iter(input: &str) -> impl Iterator<Item = Pattern> + '_ {
     input.lines().batching(|lines| {
-        let mut data = BitMatrix::<4>::new();
+        let mut data = PatternMatrix::new();
         let mut width = 0;
         let mut height = 0;
 
@@ -34,34 +39,66 @@ fn parse_input_iter(input: &str) -> impl Iterator<Item = Pattern> + '_ {
 }
 
 struct Pattern {
-    data: BitMatrix<4>,
+    data: PatternMatrix,
     width: usize,
     height: usize,
 }
 
 impl Pattern {
+    /// Transpose the pattern, so vertical reflection lines become horizontal ones.
+    ///
+    /// Only used by the tests, as a reference for [`Pattern::find_vertical_reflection_line`].
+    #[cfg(test)]
     fn transpose(&mut self) {
         self.data = self.data.transpose();
         std::mem::swap(&mut self.width, &mut self.height);
     }
 
     fn find_horizontal_reflection_line(&self, target_smudges: usize) -> Option<usize> {
-        let rows = unsafe {
-            let (prefix, rows, suffix) = self.data.bytes().align_to::<u32>();
-            assert!(prefix.is_empty());
-            assert!(suffix.is_empty());
-            rows
-        };
-
-        (1..self.height).find(|&num_rows_above| {
+        self.find_all_reflection_lines(target_smudges).next()
+    }
+
+    /// Find the first vertical reflection line with exactly `target_smudges` smudges, comparing columns directly
+    /// instead of transposing the pattern.
+    fn find_vertical_reflection_line(&self, target_smudges: usize) -> Option<usize> {
+        let mut columns = [0u64; PATTERN_BYTES * 8];
+        for (j, column) in columns.iter_mut().enumerate().take(self.width) {
+            *column = self.data.column_bits(j);
+        }
+
+        (1..self.width).find(|&num_cols_left| {
+            let num_cols_right = self.width - num_cols_left;
+            let max_offset = usize::min(num_cols_left - 1, num_cols_right - 1);
+
+            let mut smudges = 0;
+
+            for offset in 0..=max_offset {
+                let col_left = columns[num_cols_left - offset - 1];
+                let col_right = columns[num_cols_left + offset];
+
+                let diff = col_left ^ col_right;
+                smudges += diff.count_ones() as usize;
+
+                if smudges > target_smudges {
+                    return false;
+                }
+            }
+
+            smudges == target_smudges
+        })
+    }
+
+    /// Find all horizontal reflection lines with exactly `target_smudges` smudges, in increasing order.
+    fn find_all_reflection_lines(&self, target_smudges: usize) -> impl Iterator<Item = usize> + '_ {
+        (1..self.height).filter(move |&num_rows_above| {
             let num_rows_below = self.height - num_rows_above;
             let max_offset = usize::min(num_rows_above - 1, num_rows_below - 1);
 
             let mut smudges = 0;
 
             for offset in 0..=max_offset {
-                let row_above = rows[num_rows_above - offset - 1];
-                let row_below = rows[num_rows_above + offset];
+                let row_above = self.data.row_as_u64(num_rows_above - offset - 1);
+                let row_below = self.data.row_as_u64(num_rows_above + offset);
 
                 let diff = row_above ^ row_below;
                 smudges += diff.count_ones() as usize;
@@ -90,14 +127,11 @@ impl std::fmt::Display for Pattern {
 
 fn solve(input: &str, smudges: usize) -> Option<usize> {
     parse_input_iter(input)
-        .map(|mut pattern| {
+        .map(|pattern| {
             pattern
                 .find_horizontal_reflection_line(smudges)
                 .map_or_else(
-                    || {
-                        pattern.transpose();
-                        pattern.find_horizontal_reflection_line(smudges).unwrap()
-                    },
+                    || pattern.find_vertical_reflection_line(smudges).unwrap(),
                     |line| line * 100,
                 )
         })
@@ -128,4 +162,27 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(400));
     }
+
+    #[test]
+    fn test_find_all_reflection_lines() {
+        let pattern = parse_input_iter("#..\n#..\n#..\n").next().unwrap();
+        assert_eq!(pattern.find_all_reflection_lines(0).collect_vec(), [1, 2]);
+        assert_eq!(pattern.find_horizontal_reflection_line(0), Some(1));
+    }
+
+    #[test]
+    fn test_find_vertical_reflection_line() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        for mut pattern in parse_input_iter(&input) {
+            for smudges in 0..=1 {
+                let line = pattern.find_vertical_reflection_line(smudges);
+                pattern.transpose();
+                assert_eq!(line, pattern.find_horizontal_reflection_line(smudges));
+                pattern.transpose();
+            }
+        }
+
+        let pattern = parse_input_iter(&input).next().unwrap();
+        assert_eq!(pattern.find_vertical_reflection_line(0), Some(5));
+    }
 }
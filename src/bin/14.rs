@@ -1,5 +1,7 @@
 #![feature(portable_simd)]
 
+use advent_of_code::util::coord::Direction;
+use advent_of_code::util::BitMatrixBytes;
 use ahash::AHashMap;
 use std::simd::prelude::*;
 
@@ -7,6 +9,7 @@ advent_of_code::solution!(14);
 
 type BitMatrix = advent_of_code::util::BitMatrix<16>;
 
+#[derive(Clone)]
 struct Field {
     dim: usize,
     rotation: usize,
@@ -90,16 +93,10 @@ impl Field {
 
     fn total_load_with<F: Fn(u32, u32) -> u32>(&self, load_factor: F) -> u32 {
         let start_i = self.start_i();
-        let rocks_rows = self.rocks.rows();
-
-        rocks_rows
-            .iter()
-            .skip(start_i)
-            .take(self.dim)
-            .enumerate()
-            .map(|(i, row)| {
-                row.iter().map(|&x| x.count_ones()).sum::<u32>()
-                    * load_factor(i as u32, self.dim as u32)
+
+        (0..self.dim)
+            .map(|i| {
+                self.rocks.row_count_ones(start_i + i) * load_factor(i as u32, self.dim as u32)
             })
             .sum()
     }
@@ -116,39 +113,101 @@ impl Field {
         self.total_load_with(|i, _dim| i + 1)
     }
 
+    /// Compute the total load on the North, East, South and West support beams (in that order), regardless of the
+    /// direction the field is facing.
+    fn loads_all_directions(&self) -> [u32; 4] {
+        let mut field = self.clone();
+        let mut loads = [0; 4];
+        for _ in 0..4 {
+            // After `rotation` right rotations, the side facing up is N, W, S, E respectively
+            loads[(4 - field.rotation) % 4] = field.total_load_with(|i, dim| dim - i);
+            field.rotate_right();
+        }
+        loads
+    }
+
+    /// Rotate the field right until it has the given rotation.
+    fn rotate_to(&mut self, rotation: usize) {
+        while self.rotation != rotation {
+            self.rotate_right();
+        }
+    }
+
+    /// Roll all rocks in the given direction (with `Up` being North), by temporarily rotating the field so that
+    /// direction faces up, rolling, and rotating back.
+    fn roll(&mut self, direction: Direction) {
+        let rotation = self.rotation;
+        self.roll_facing(direction);
+        self.rotate_to(rotation);
+    }
+
+    /// Like [`Field::roll`], but leaves the field rotated so the direction faces up. Rolling in the order North, West,
+    /// South, East then only ever rotates forward, which is what [`Field::cycle`] relies on.
+    fn roll_facing(&mut self, direction: Direction) {
+        // Number of right rotations needed to make the direction face up, from the unrotated field
+        let target_rotation = match direction {
+            Direction::Up => 0,
+            Direction::Left => 1,
+            Direction::Down => 2,
+            Direction::Right => 3,
+        };
+        self.rotate_to(target_rotation);
+        self.roll_up();
+    }
+
     fn cycle(&mut self) -> u32 {
-        self.roll_up(); // rolled north
-        self.rotate_right();
-        self.roll_up(); // rolled west
-        self.rotate_right();
-        self.roll_up(); // rolled south
+        self.roll_facing(Direction::Up);
+        self.roll_facing(Direction::Left);
+        self.roll_facing(Direction::Down);
         let result = self.total_load_reverse();
-        self.rotate_right();
-        self.roll_up(); // rolled east
-        self.rotate_right();
+        self.roll_facing(Direction::Right);
+        self.rotate_to(0);
         result
     }
 }
 
-type FieldCacheKey = [u8; 16 * 100];
+/// The rock bytes of the rows covered by the field, sized to the field's dimensions.
+type FieldCacheKey = BitMatrixBytes;
 
 impl Field {
     fn cache_key(&self) -> FieldCacheKey {
-        let mut key = [0; 16 * 100];
-        let start = self.start_i() * 16;
-        let len = self.dim * 16;
-        key[..len].copy_from_slice(&self.rocks.bytes()[start..start + len]);
-        key
+        let start_i = self.start_i();
+        self.rocks.rows_bytes(start_i..start_i + self.dim)
+    }
+
+    /// The rock rows covered by the field, i.e. the rows [`Field::cache_key`] copies.
+    fn rock_rows(&self) -> &[Simd<u8, 16>] {
+        let start_i = self.start_i();
+        &self.rocks.rows_simd()[start_i..start_i + self.dim]
+    }
+
+    /// Returns `true` if both fields have their rocks in the same places, comparing them in place without copying them
+    /// like [`Field::cache_key`]. Only meaningful for fields from the same input, in the same rotation.
+    fn same_rocks(&self, other: &Self) -> bool {
+        debug_assert_eq!(self.rotation, other.rotation);
+        self.rock_rows() == other.rock_rows()
     }
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
     let mut field = Field::from_input(input);
-    field.roll_up();
+    field.roll(Direction::Up);
     Some(field.total_load())
 }
 
+const TOTAL_CYCLES: usize = 1_000_000_000;
+
+/// Maximum number of configurations to remember before falling back to Floyd's cycle detection.
+const MAX_CACHE_SIZE: usize = 10_000;
+
 pub fn part_two(input: &str) -> Option<u32> {
+    solve_part_two(input, MAX_CACHE_SIZE)
+}
+
+/// Finds the total load after [`TOTAL_CYCLES`] cycles, remembering at most `max_cache_size` configurations while
+/// looking for a repeat. If that's not enough, uses Floyd's cycle detection instead, which needs constant memory but
+/// has to run the cycles a few more times.
+fn solve_part_two(input: &str, max_cache_size: usize) -> Option<u32> {
     let mut field = Field::from_input(input);
     let mut cycles = 0;
 
@@ -159,16 +218,75 @@ pub fn part_two(input: &str) -> Option<u32> {
         let total_load = field.cycle();
         cycles += 1;
 
+        if cfg!(feature = "debug_output") {
+            println!("cycle {}: loads {:?}", cycles, field.loads_all_directions());
+        }
+
         if let Some(prev_cycles) = cache.insert(field.cache_key(), cycles) {
             let cycles_repeat = cycles - prev_cycles;
-            let cycles_remaining = (1_000_000_000 - cycles) % cycles_repeat;
+            let cycles_remaining = (TOTAL_CYCLES - cycles) % cycles_repeat;
             return Some(total_loads[total_loads.len() - cycles_repeat + cycles_remaining]);
         }
 
+        if cache.len() > max_cache_size {
+            return Some(solve_part_two_floyd(field, cycles));
+        }
+
         total_loads.push(total_load);
     }
 }
 
+/// Finds the total load after [`TOTAL_CYCLES`] cycles using Floyd's (tortoise and hare) cycle detection, given the
+/// `field` after `cycles_done` cycles.
+fn solve_part_two_floyd(field: Field, cycles_done: usize) -> u32 {
+    let cycles_remaining = TOTAL_CYCLES - cycles_done;
+
+    // Find a configuration inside the loop, where the hare has done twice as many cycles as the tortoise
+    let mut tortoise = field.clone();
+    tortoise.cycle();
+    let mut hare = tortoise.clone();
+    hare.cycle();
+    while !tortoise.same_rocks(&hare) {
+        tortoise.cycle();
+        hare.cycle();
+        hare.cycle();
+    }
+
+    // Find the length of the pre-period
+    let mut pre_period = 0;
+    tortoise = field.clone();
+    while !tortoise.same_rocks(&hare) {
+        tortoise.cycle();
+        hare.cycle();
+        pre_period += 1;
+    }
+
+    // Find the length of the period
+    let mut period = 1;
+    hare = tortoise.clone();
+    hare.cycle();
+    while !tortoise.same_rocks(&hare) {
+        hare.cycle();
+        period += 1;
+    }
+
+    // The last cycle is reached before the loop
+    if cycles_remaining < pre_period {
+        let mut field = field;
+        for _ in 0..cycles_remaining {
+            field.cycle();
+        }
+        return field.total_load();
+    }
+
+    // The tortoise is now `pre_period` cycles in, and the configuration after `TOTAL_CYCLES` cycles is the same as after
+    // this many more
+    for _ in 0..(cycles_remaining - pre_period) % period {
+        tortoise.cycle();
+    }
+    tortoise.total_load()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,4 +302,86 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(64));
     }
+
+    #[test]
+    fn test_part_two_floyd() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+
+        // The example only repeats after 10 cycles, so a cache of 2 configurations forces the fallback
+        assert_eq!(solve_part_two(&input, 2), Some(64));
+        assert_eq!(solve_part_two_floyd(Field::from_input(&input), 0), 64);
+    }
+
+    #[test]
+    fn test_count_ones() {
+        let mut field = Field::from_input(&advent_of_code::template::read_file("examples", DAY));
+        for _ in 0..4 {
+            let scalar_count = field
+                .rocks
+                .bytes()
+                .iter()
+                .map(|x| x.count_ones())
+                .sum::<u32>();
+            assert_eq!(field.rocks.count_ones(), scalar_count);
+            assert_eq!(scalar_count, 18);
+            field.roll_up();
+            field.rotate_right();
+        }
+    }
+
+    #[test]
+    fn test_part_two_tall_grid() {
+        // A single rock ends up in the south-east corner after one cycle, and stays there
+        let dim = 110;
+        let mut input = String::new();
+        for i in 0..dim {
+            for j in 0..dim {
+                input.push(if (i, j) == (dim / 2, dim / 2) {
+                    'O'
+                } else {
+                    '.'
+                });
+            }
+            input.push('\n');
+        }
+
+        let result = part_two(&input);
+        assert_eq!(result, Some(1));
+    }
+
+    #[test]
+    fn test_loads_all_directions() {
+        let mut field = Field::from_input(&advent_of_code::template::read_file("examples", DAY));
+        field.cycle();
+        assert_eq!(field.loads_all_directions(), [87, 106, 111, 92]);
+        assert_eq!(field.loads_all_directions()[0], field.total_load());
+    }
+
+    #[test]
+    fn test_roll() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+
+        let mut rolled_up = Field::from_input(&input);
+        rolled_up.roll_up();
+
+        let mut rolled_north = Field::from_input(&input);
+        rolled_north.roll(Direction::Up);
+        assert_eq!(rolled_north.rotation, 0);
+        assert_eq!(rolled_north.total_load(), rolled_up.total_load());
+        assert_eq!(rolled_north.total_load(), 136);
+
+        // Rolling south puts every rock against the south edge or a block
+        let mut rolled_south = Field::from_input(&input);
+        rolled_south.roll(Direction::Down);
+        assert_eq!(rolled_south.rotation, 0);
+        assert_eq!(rolled_south.loads_all_directions()[2], 132);
+
+        let mut rolled_south_facing = Field::from_input(&input);
+        rolled_south_facing.roll_facing(Direction::Down);
+        assert_eq!(rolled_south_facing.rotation, 2);
+        assert_eq!(
+            rolled_south_facing.total_load_reverse(),
+            rolled_south.total_load()
+        );
+    }
 }
@@ -2,6 +2,8 @@ use itertools::chain;
 
 use advent_of_code::util::coord::Direction;
 use advent_of_code::util::{Indexer, LinearIndexer, VecMap, VecSet, VecTable};
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::DiGraph;
 
 advent_of_code::solution!(16);
 
@@ -58,22 +60,7 @@ impl Indexer<DirectedCoord> for StartingCoordIndexer {
 }
 
 fn parse_input(input: &str) -> VecTable<Coord, char, CoordIndexer> {
-    let mut width = None;
-    let data = input
-        .lines()
-        .flat_map(|line| {
-            if width.is_none() {
-                width = Some(line.len());
-            } else {
-                debug_assert_eq!(width, Some(line.len()));
-            }
-            line.chars()
-        })
-        .collect::<Vec<char>>();
-    let width = width.unwrap() as CoordT;
-    let height = (data.len() as CoordT) / width;
-    let indexer = CoordIndexer::new(width, height);
-    VecTable::from_vec(data, indexer)
+    VecTable::from_grid_str(input, |c| c).unwrap()
 }
 
 fn build_nodes(
@@ -269,64 +256,226 @@ fn build_nodes(
     (nodes, starting_nodes)
 }
 
-/// Computes the number of tiles beams will travel through if they start at the given node, excluding loops.
-/// This functions as an upper bound on the number of energized tiles.
-fn build_length_remaining(nodes: &[Node]) -> VecMap<NodeIndex, u32, LinearIndexer<NodeIndex>> {
-    let mut length_remaining = VecMap::new(LinearIndexer::new(nodes.len() as NodeIndex));
+/// Returns the tiles a beam travels through from `from` to `to`, excluding `from` itself.
+fn segment_tiles(from: Coord, to: Coord) -> impl Iterator<Item = Coord> {
+    let min_x = from.x.min(to.x);
+    let max_x = from.x.max(to.x);
+    let min_y = from.y.min(to.y);
+    let max_y = from.y.max(to.y);
+
+    debug_assert!(min_x == max_x || min_y == max_y);
+
+    (min_x..=max_x)
+        .flat_map(move |x| (min_y..=max_y).map(move |y| Coord { x, y }))
+        .filter(move |&coord| coord != from)
+}
+
+/// Groups the nodes into strongly connected components, i.e. sets of nodes that can all reach each other. Returns the
+/// components in reverse topological order (so a component only leads to components before it), along with the index
+/// of the component each node is in.
+fn build_components(nodes: &[Node]) -> (Vec<Vec<NodeIndex>>, Vec<usize>) {
+    let mut graph = DiGraph::<(), (), NodeIndex>::with_capacity(nodes.len(), nodes.len() * 2);
+    for _ in nodes {
+        graph.add_node(());
+    }
+    for (node_index, node) in nodes.iter().enumerate() {
+        for next_node_index in node.next.iter().flatten() {
+            graph.add_edge(
+                (node_index as NodeIndex).into(),
+                (*next_node_index).into(),
+                (),
+            );
+        }
+    }
+
+    let components = tarjan_scc(&graph)
+        .into_iter()
+        .map(|component| {
+            component
+                .into_iter()
+                .map(|node_index| node_index.index() as NodeIndex)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
 
-    for node_index in 0..(nodes.len() as NodeIndex) {
-        if length_remaining.contains_key(&node_index) {
-            continue;
+    let mut component_indices = vec![usize::MAX; nodes.len()];
+    for (component_index, component) in components.iter().enumerate() {
+        for node_index in component {
+            component_indices[*node_index as usize] = component_index;
         }
+    }
+
+    (components, component_indices)
+}
 
-        build_length_remaining_inner(
-            nodes,
-            node_index,
-            &mut length_remaining,
-            &mut VecSet::new(LinearIndexer::new(nodes.len() as NodeIndex)),
+/// Computes the number of distinct tiles beams will travel through if they start at the given node, not counting the
+/// tile of the node itself. This functions as an upper bound on the number of energized tiles.
+///
+/// Nodes in the same strongly connected component reach the same tiles, so the tiles are collected once per component,
+/// as the union of the tiles on the segments leaving the component and the tiles reached by the components they lead
+/// to. Taking the union rather than summing the segment lengths means tiles reachable along several paths (e.g. from
+/// both sides of a splitter) are only counted once.
+fn build_length_remaining(
+    nodes: &[Node],
+    indexer: CoordIndexer,
+) -> VecMap<NodeIndex, u32, LinearIndexer<NodeIndex>> {
+    let (components, component_indices) = build_components(nodes);
+
+    // Number of edges into each component from other components, so its tiles can be freed after the last one is
+    // processed
+    let mut uses_remaining = vec![0u32; components.len()];
+    for (node_index, node) in nodes.iter().enumerate() {
+        for next_node_index in node.next.iter().flatten() {
+            let next_component_index = component_indices[*next_node_index as usize];
+            if next_component_index != component_indices[node_index] {
+                uses_remaining[next_component_index] += 1;
+            }
+        }
+    }
+
+    let words = indexer.len().div_ceil(64);
+    let mut component_tiles = Vec::<Vec<u64>>::with_capacity(components.len());
+    let mut component_lengths = Vec::<u32>::with_capacity(components.len());
+    let mut free_tiles = Vec::<Vec<u64>>::new();
+    for (component_index, component) in components.iter().enumerate() {
+        let mut tiles = free_tiles.pop().unwrap_or_else(|| vec![0; words]);
+        tiles.fill(0);
+
+        for node_index in component {
+            let node = &nodes[*node_index as usize];
+            for next_node_index in node.next.iter().flatten() {
+                for coord in segment_tiles(node.coord, nodes[*next_node_index as usize].coord) {
+                    let index = indexer.index_for(&coord);
+                    tiles[index / 64] |= 1 << (index % 64);
+                }
+
+                let next_component_index = component_indices[*next_node_index as usize];
+                if next_component_index != component_index {
+                    let next_tiles = &mut component_tiles[next_component_index];
+                    for (word, next_word) in tiles.iter_mut().zip(next_tiles.iter()) {
+                        *word |= next_word;
+                    }
+
+                    uses_remaining[next_component_index] -= 1;
+                    if uses_remaining[next_component_index] == 0 {
+                        free_tiles.push(std::mem::take(next_tiles));
+                    }
+                }
+            }
+        }
+
+        component_lengths.push(tiles.iter().map(|word| word.count_ones()).sum());
+        component_tiles.push(tiles);
+    }
+
+    let mut length_remaining = VecMap::new(LinearIndexer::new(nodes.len() as NodeIndex));
+    for (node_index, &component_index) in component_indices.iter().enumerate() {
+        length_remaining.insert(
+            &(node_index as NodeIndex),
+            component_lengths[component_index],
         );
     }
+    length_remaining
+}
 
+/// Like [`build_length_remaining`], but sums the lengths of the segments leaving each component and the lengths of the
+/// components they lead to, so tiles reachable along several paths are counted more than once. The sums are capped at
+/// the number of tiles in the map.
+#[cfg(test)]
+fn build_length_remaining_summed(
+    nodes: &[Node],
+    indexer: CoordIndexer,
+) -> VecMap<NodeIndex, u32, LinearIndexer<NodeIndex>> {
+    let (components, component_indices) = build_components(nodes);
+
+    let mut component_lengths = Vec::<u32>::with_capacity(components.len());
+    for (component_index, component) in components.iter().enumerate() {
+        let mut length = 0u32;
+        for node_index in component {
+            let node = &nodes[*node_index as usize];
+            for next_node_index in node.next.iter().flatten() {
+                let next_node = &nodes[*next_node_index as usize];
+                let dist = node.coord.x.abs_diff(next_node.coord.x)
+                    + node.coord.y.abs_diff(next_node.coord.y);
+                length = length.saturating_add(dist);
+
+                let next_component_index = component_indices[*next_node_index as usize];
+                if next_component_index != component_index {
+                    length = length.saturating_add(component_lengths[next_component_index]);
+                }
+            }
+        }
+        component_lengths.push(length.min(indexer.len() as u32));
+    }
+
+    let mut length_remaining = VecMap::new(LinearIndexer::new(nodes.len() as NodeIndex));
+    for (node_index, &component_index) in component_indices.iter().enumerate() {
+        length_remaining.insert(
+            &(node_index as NodeIndex),
+            component_lengths[component_index],
+        );
+    }
     length_remaining
 }
 
-fn build_length_remaining_inner(
+/// Returns `true` if a beam can't energize more than `current_max_energized_tiles` tiles, given that it has energized
+/// `energized_count` tiles so far and `length_remaining_in_stack` is the sum of the remaining lengths of the nodes in
+/// its stack.
+///
+/// This relies on the invariant that every tile the beam will still energize is reachable from a node in the stack.
+/// The remaining length of a node (see [`build_length_remaining`]) is the number of distinct tiles reachable from its
+/// strongly connected component, so the sum over the stack bounds the number of tiles left to energize.
+fn can_prune(
+    energized_count: u32,
+    length_remaining_in_stack: u32,
+    current_max_energized_tiles: u32,
+) -> bool {
+    energized_count + length_remaining_in_stack <= current_max_energized_tiles
+}
+
+fn compute_energized_tiles(
     nodes: &[Node],
     node_index: NodeIndex,
-    length_remaining: &mut VecMap<NodeIndex, u32, LinearIndexer<NodeIndex>>,
-    visited: &mut VecSet<NodeIndex, LinearIndexer<NodeIndex>>,
+    indexer: CoordIndexer,
+    length_remaining_map: &VecMap<NodeIndex, u32, LinearIndexer<NodeIndex>>,
+    current_max_energized_tiles: u32,
 ) -> u32 {
-    if !visited.insert(node_index) {
-        return 0;
-    }
+    compute_energized_tiles_counted(
+        nodes,
+        node_index,
+        indexer,
+        length_remaining_map,
+        current_max_energized_tiles,
+        &mut (),
+    )
+}
 
-    if let Some(length) = length_remaining.get(&node_index) {
-        return *length;
-    }
+/// Counts the nodes expanded by [`compute_energized_tiles_counted`]. The solutions count with `()`, which compiles to
+/// nothing, the tests with `u32`.
+trait VisitCounter {
+    fn visit(&mut self);
+}
 
-    let node = &nodes[node_index as usize];
-    let length = node
-        .next
-        .iter()
-        .flatten()
-        .map(|next_node_index| {
-            let next_node = &nodes[*next_node_index as usize];
-            let dist =
-                node.coord.x.abs_diff(next_node.coord.x) + node.coord.y.abs_diff(next_node.coord.y);
+impl VisitCounter for () {
+    #[inline]
+    fn visit(&mut self) {}
+}
 
-            build_length_remaining_inner(nodes, *next_node_index, length_remaining, visited) + dist
-        })
-        .sum::<u32>();
-    length_remaining.insert(&node_index, length);
-    length
+#[cfg(test)]
+impl VisitCounter for u32 {
+    fn visit(&mut self) {
+        *self += 1;
+    }
 }
 
-fn compute_energized_tiles(
+/// Like [`compute_energized_tiles`], but also counts the nodes that are expanded with `node_visits`.
+fn compute_energized_tiles_counted(
     nodes: &[Node],
     node_index: NodeIndex,
     indexer: CoordIndexer,
     length_remaining_map: &VecMap<NodeIndex, u32, LinearIndexer<NodeIndex>>,
     current_max_energized_tiles: u32,
+    node_visits: &mut impl VisitCounter,
 ) -> u32 {
     let mut stack = Vec::<NodeIndex>::new();
     let mut visited = VecSet::new(LinearIndexer::new(nodes.len() as NodeIndex));
@@ -342,9 +491,14 @@ fn compute_energized_tiles(
     let mut length_remaining_in_stack = *length_remaining_map.get(&node_index).unwrap();
 
     while let Some(node_index) = stack.pop() {
-        if energized_count + length_remaining_in_stack <= current_max_energized_tiles {
+        if can_prune(
+            energized_count,
+            length_remaining_in_stack,
+            current_max_energized_tiles,
+        ) {
             return energized_count;
         }
+        node_visits.visit();
 
         length_remaining_in_stack -= length_remaining_map.get(&node_index).unwrap();
 
@@ -356,20 +510,9 @@ fn compute_energized_tiles(
             }
 
             let next_node = &nodes[*next_node_index as usize];
-
-            let min_x = next_node.coord.x.min(node.coord.x);
-            let max_x = next_node.coord.x.max(node.coord.x);
-            let min_y = next_node.coord.y.min(node.coord.y);
-            let max_y = next_node.coord.y.max(node.coord.y);
-
-            debug_assert!(min_x == max_x || min_y == max_y);
-
-            for x in min_x..=max_x {
-                for y in min_y..=max_y {
-                    let coord = Coord { x, y };
-                    if energized.insert(coord) {
-                        energized_count += 1;
-                    }
+            for coord in segment_tiles(node.coord, next_node.coord) {
+                if energized.insert(coord) {
+                    energized_count += 1;
                 }
             }
 
@@ -385,7 +528,7 @@ pub fn part_one(input: &str) -> Option<u32> {
     let map = parse_input(input);
     let (nodes, starting_nodes) = build_nodes(&map);
 
-    let length_remaining_map = build_length_remaining(&nodes);
+    let length_remaining_map = build_length_remaining(&nodes, *map.indexer());
 
     compute_energized_tiles(
         &nodes,
@@ -402,21 +545,17 @@ pub fn part_one(input: &str) -> Option<u32> {
     .into()
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let map = parse_input(input);
-    let (nodes, starting_nodes) = build_nodes(&map);
-
-    let length_remaining_map = build_length_remaining(&nodes);
-
-    let width = map.indexer().width;
-    let height = map.indexer().height;
+/// Returns the beams entering the map from each tile along its edge.
+fn starting_beams(indexer: CoordIndexer) -> impl Iterator<Item = DirectedCoord> {
+    let width = indexer.width;
+    let height = indexer.height;
 
     chain![
         (0..width).map(|x| DirectedCoord {
             coord: Coord { x, y: 0 },
             direction: Direction::Down,
         }),
-        (0..width).map(|x| DirectedCoord {
+        (0..width).map(move |x| DirectedCoord {
             coord: Coord { x, y: height - 1 },
             direction: Direction::Up,
         }),
@@ -424,27 +563,110 @@ pub fn part_two(input: &str) -> Option<u32> {
             coord: Coord { x: 0, y },
             direction: Direction::Right,
         }),
-        (0..height).map(|y| DirectedCoord {
+        (0..height).map(move |y| DirectedCoord {
             coord: Coord { x: width - 1, y },
             direction: Direction::Left,
         }),
     ]
-    .fold(0, |current_max_energized_count, beam_front| {
-        compute_energized_tiles(
-            &nodes,
-            *starting_nodes.get(&beam_front).unwrap(),
-            *map.indexer(),
-            &length_remaining_map,
-            current_max_energized_count,
-        )
-        .max(current_max_energized_count)
-    })
-    .into()
+}
+
+/// Straightforward BFS over the directed beam states, returning the number of energized tiles when a beam enters the
+/// map at `start`. This is much slower than [`compute_energized_tiles`], but easy to verify, so it serves as a
+/// reference implementation.
+#[cfg(test)]
+fn compute_energized_tiles_reference(
+    map: &VecTable<Coord, char, CoordIndexer>,
+    start: DirectedCoord,
+) -> u32 {
+    use std::collections::VecDeque;
+
+    let indexer = *map.indexer();
+    let mut visited = VecSet::directed(indexer.width, indexer.height);
+    let mut energized = VecSet::new(indexer);
+    let mut energized_count = 0;
+
+    let mut queue = VecDeque::from([start]);
+    visited.insert(start);
+
+    while let Some(DirectedCoord { coord, direction }) = queue.pop_front() {
+        if energized.insert(coord) {
+            energized_count += 1;
+        }
+
+        let next_directions = match (map.get(&coord), direction) {
+            ('/', Direction::Right) | ('\\', Direction::Left) => [Some(Direction::Up), None],
+            ('/', Direction::Left) | ('\\', Direction::Right) => [Some(Direction::Down), None],
+            ('/', Direction::Up) | ('\\', Direction::Down) => [Some(Direction::Right), None],
+            ('/', Direction::Down) | ('\\', Direction::Up) => [Some(Direction::Left), None],
+            ('|', Direction::Left | Direction::Right) => {
+                [Some(Direction::Up), Some(Direction::Down)]
+            }
+            ('-', Direction::Up | Direction::Down) => {
+                [Some(Direction::Left), Some(Direction::Right)]
+            }
+            _ => [Some(direction), None],
+        };
+
+        for direction in next_directions.into_iter().flatten() {
+            let Coord { x, y } = coord;
+            let next_coord = match direction {
+                Direction::Up if y > 0 => Coord::new(x, y - 1),
+                Direction::Right if x + 1 < indexer.width => Coord::new(x + 1, y),
+                Direction::Down if y + 1 < indexer.height => Coord::new(x, y + 1),
+                Direction::Left if x > 0 => Coord::new(x - 1, y),
+                _ => continue,
+            };
+            let next = DirectedCoord {
+                coord: next_coord,
+                direction,
+            };
+            if visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    energized_count
+}
+
+/// Returns the maximum number of energized tiles over all beams entering the map, along with the first beam that
+/// achieves it.
+fn solve_part_two(input: &str) -> Option<(u32, DirectedCoord)> {
+    let map = parse_input(input);
+    let (nodes, starting_nodes) = build_nodes(&map);
+
+    let length_remaining_map = build_length_remaining(&nodes, *map.indexer());
+
+    let (max_energized_count, best_beam_front) = starting_beams(*map.indexer()).fold(
+        (0, None),
+        |(current_max_energized_count, best_beam_front), beam_front| {
+            let energized_count = compute_energized_tiles(
+                &nodes,
+                *starting_nodes.get(&beam_front).unwrap(),
+                *map.indexer(),
+                &length_remaining_map,
+                current_max_energized_count,
+            );
+            // Pruned beams report at most the current max, so they never replace the best beam
+            if energized_count > current_max_energized_count {
+                (energized_count, Some(beam_front))
+            } else {
+                (current_max_energized_count, best_beam_front)
+            }
+        },
+    );
+
+    Some((max_energized_count, best_beam_front?))
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    solve_part_two(input).map(|(max_energized_count, _)| max_energized_count)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::util::test::seeded_random;
 
     #[test]
     fn test_part_one() {
@@ -457,4 +679,113 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(51));
     }
+
+    #[test]
+    fn test_solve_part_two_start() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let (max_energized_count, beam_front) = solve_part_two(&input).unwrap();
+        assert_eq!(max_energized_count, 51);
+
+        let map = parse_input(&input);
+        let (nodes, starting_nodes) = build_nodes(&map);
+        let energized_count = compute_energized_tiles(
+            &nodes,
+            *starting_nodes.get(&beam_front).unwrap(),
+            *map.indexer(),
+            &build_length_remaining(&nodes, *map.indexer()),
+            0,
+        );
+        assert_eq!(energized_count, 51);
+        assert_eq!(compute_energized_tiles_reference(&map, beam_front), 51);
+    }
+
+    #[test]
+    fn test_pruning() {
+        // Pseudo-random map, checked after the example
+        let mut next_random = seeded_random();
+        let random_map = (0..40)
+            .map(|_| {
+                (0..40)
+                    .map(|_| ['.', '.', '.', '.', '.', '.', '/', '\\', '|', '-'][next_random(10)])
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect::<String>();
+
+        for (input, expected_max) in [
+            (
+                advent_of_code::template::read_file("examples", DAY),
+                Some(51),
+            ),
+            (random_map, None),
+        ] {
+            let map = parse_input(&input);
+            let (nodes, starting_nodes) = build_nodes(&map);
+
+            // Runs part two with the given bound, returning the max energized count and the number of node visits
+            let run = |length_remaining_map: &VecMap<NodeIndex, u32, LinearIndexer<NodeIndex>>| {
+                let mut node_visits = 0u32;
+                let mut max_energized_count = 0;
+                for beam in starting_beams(*map.indexer()) {
+                    let energized_count = compute_energized_tiles_counted(
+                        &nodes,
+                        *starting_nodes.get(&beam).unwrap(),
+                        *map.indexer(),
+                        length_remaining_map,
+                        max_energized_count,
+                        &mut node_visits,
+                    );
+
+                    // The bound must never prune a beam that beats the current max
+                    let reference_count = compute_energized_tiles_reference(&map, beam);
+                    if reference_count > max_energized_count {
+                        assert_eq!(energized_count, reference_count);
+                    }
+                    max_energized_count = max_energized_count.max(energized_count);
+                }
+                (max_energized_count, node_visits)
+            };
+
+            let (summed_max_energized_count, summed_visits) =
+                run(&build_length_remaining_summed(&nodes, *map.indexer()));
+            let (max_energized_count, visits) =
+                run(&build_length_remaining(&nodes, *map.indexer()));
+            if let Some(expected_max) = expected_max {
+                assert_eq!(max_energized_count, expected_max);
+            }
+            assert_eq!(summed_max_energized_count, max_energized_count);
+            assert!(visits < summed_visits);
+        }
+    }
+
+    #[test]
+    fn test_reference() {
+        let map = parse_input(&advent_of_code::template::read_file("examples", DAY));
+        let (nodes, starting_nodes) = build_nodes(&map);
+        let length_remaining_map = build_length_remaining(&nodes, *map.indexer());
+
+        let start = DirectedCoord {
+            coord: Coord { x: 0, y: 0 },
+            direction: Direction::Right,
+        };
+        assert_eq!(compute_energized_tiles_reference(&map, start), 46);
+
+        let mut max_energized_count = 0;
+        for beam in starting_beams(*map.indexer()) {
+            let energized_count = compute_energized_tiles_reference(&map, beam);
+            let node_index = *starting_nodes.get(&beam).unwrap();
+            assert_eq!(
+                compute_energized_tiles(
+                    &nodes,
+                    node_index,
+                    *map.indexer(),
+                    &length_remaining_map,
+                    0
+                ),
+                energized_count
+            );
+            max_energized_count = max_energized_count.max(energized_count);
+        }
+        assert_eq!(max_energized_count, 51);
+    }
 }
@@ -1,8 +1,14 @@
-use bucket_queue::{BucketQueue, LastInFirstOutQueue};
+use std::collections::VecDeque;
+
+use bucket_queue::{BucketQueue, FirstInFirstOutQueue, LastInFirstOutQueue};
+use num::traits::AsPrimitive;
+use num::PrimInt;
 
 use advent_of_code::util::coord::Direction;
-use advent_of_code::util::shortest_path::{CostMap, OpenSet, Problem};
-use advent_of_code::util::{shortest_path, Indexer, VecMap, VecSet, VecTable};
+use advent_of_code::util::shortest_path::{CostMap, OpenSet, Problem, TieBreak};
+use advent_of_code::util::{
+    shortest_path, BitPackIndexer, Indexer, LinearIndexer, VecMap, VecSet, VecTable,
+};
 
 advent_of_code::solution!(17);
 
@@ -13,6 +19,11 @@ type CoordIndexer = advent_of_code::util::coord::CoordIndexer<CoordT>;
 type CoordIndex = u16;
 type Cost = u16;
 
+/// Integer types that can be used as the cost of a path. The default is [`Cost`], which is large enough for the
+/// puzzle input; wider types can be used for larger grids.
+trait CostT: PrimInt + From<u8> + From<CoordT> + AsPrimitive<usize> {}
+impl<T> CostT for T where T: PrimInt + From<u8> + From<CoordT> + AsPrimitive<usize> {}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Axis {
     Horizontal,
@@ -43,16 +54,18 @@ struct State {
 
 #[derive(Clone, Copy)]
 struct StateIndexer {
-    grid_len: usize,
+    indexer: BitPackIndexer<LinearIndexer, 1>,
 }
 impl StateIndexer {
     fn new(grid_len: usize) -> Self {
-        Self { grid_len }
+        Self {
+            indexer: BitPackIndexer::new(LinearIndexer::new(grid_len)),
+        }
     }
 }
 impl Indexer<State> for StateIndexer {
     fn len(&self) -> usize {
-        self.grid_len * 2
+        self.indexer.len()
     }
 
     fn index_for(&self, key: &State) -> usize {
@@ -61,38 +74,23 @@ impl Indexer<State> for StateIndexer {
             Axis::Horizontal => 0,
             Axis::Vertical => 1,
         };
-        (coord_index << 1) + axis
+        self.indexer.index_for(&(coord_index, axis))
     }
 }
 
-fn parse_input(input: &str) -> VecTable<Coord, Cost, CoordIndexer> {
-    let mut width = None;
-    let data = input
-        .lines()
-        .flat_map(|line| {
-            if width.is_none() {
-                width = Some(line.len());
-            } else {
-                debug_assert_eq!(width, Some(line.len()));
-            }
-            line.chars().map(|c| ((c as u8) - b'0') as Cost)
-        })
-        .collect::<Vec<_>>();
-    let width = width.unwrap();
-    let height = data.len() / width;
-    let indexer = CoordIndexer::new(width as CoordT, height as CoordT);
-    VecTable::from_vec(data, indexer)
+fn parse_input<C: CostT>(input: &str) -> VecTable<Coord, C, CoordIndexer> {
+    VecTable::from_grid_str(input, |c| <C as From<u8>>::from((c as u8) - b'0')).unwrap()
 }
 
-struct ClumsyCrucibleProblem {
-    grid: Box<[Cost]>,
+struct ClumsyCrucibleProblem<C = Cost> {
+    grid: Box<[C]>,
     grid_width: CoordT,
     grid_height: CoordT,
     min_steps: CoordT,
     max_steps: CoordT,
 }
 
-impl ClumsyCrucibleProblem {
+impl<C> ClumsyCrucibleProblem<C> {
     fn source_index(&self) -> CoordIndex {
         (self.grid.len() - 1) as CoordIndex
     }
@@ -101,9 +99,9 @@ impl ClumsyCrucibleProblem {
     }
 }
 
-impl Problem for ClumsyCrucibleProblem {
+impl<C: CostT> Problem for ClumsyCrucibleProblem<C> {
     type State = State;
-    type Cost = Cost;
+    type Cost = C;
 
     fn sources(&self) -> impl IntoIterator<Item = Self::State> {
         [Axis::Horizontal, Axis::Vertical].map(move |axis| {
@@ -149,25 +147,25 @@ impl Problem for ClumsyCrucibleProblem {
                 };
 
                 let mut next_coord_index = coord_index;
-                let mut next_cost = 0;
+                let mut next_cost = C::zero();
 
                 let num_pre_steps = self.min_steps - 1;
                 let num_steps = self.max_steps.min(steps_to_edge) - num_pre_steps;
 
                 (0..num_pre_steps).for_each(|_| {
-                    next_cost += self.grid[next_coord_index as usize];
+                    next_cost = next_cost + self.grid[next_coord_index as usize];
                     next_coord_index = next_coord_index.wrapping_add(coord_step);
                 });
 
                 (0..num_steps).map(move |_| {
-                    next_cost += self.grid[next_coord_index as usize];
+                    next_cost = next_cost + self.grid[next_coord_index as usize];
                     next_coord_index = next_coord_index.wrapping_add(coord_step);
 
                     let next_state = State {
                         coord_index: next_coord_index,
                         axis: axis.orthogonal(),
                     };
-                    (next_state, next_cost as Cost)
+                    (next_state, next_cost)
                 })
             })
     }
@@ -180,28 +178,68 @@ impl Problem for ClumsyCrucibleProblem {
         let target_x = self.target_index() % self.grid_width;
         let target_y = self.target_index() / self.grid_width;
 
-        target_x.abs_diff(x) + target_y.abs_diff(y)
+        <C as From<CoordT>>::from(target_x.abs_diff(x) + target_y.abs_diff(y))
     }
 }
 
-struct MyOpenSet {
-    queue: BucketQueue<Vec<State>>,
+/// Bucket queue of states. Its bucket type determines the order in which states with equal cost are popped: `Vec`
+/// buckets pop them LIFO, `VecDeque` buckets FIFO.
+trait StateQueue {
+    fn new() -> Self;
+    fn insert(&mut self, state: State, cost: usize);
+    fn pop_min(&mut self) -> Option<State>;
+}
+
+impl StateQueue for BucketQueue<Vec<State>> {
+    fn new() -> Self {
+        BucketQueue::new()
+    }
+
+    #[inline]
+    fn insert(&mut self, state: State, cost: usize) {
+        self.push(state, cost)
+    }
+
+    #[inline]
+    fn pop_min(&mut self) -> Option<State> {
+        LastInFirstOutQueue::pop_min(self)
+    }
+}
+
+impl StateQueue for BucketQueue<VecDeque<State>> {
+    fn new() -> Self {
+        BucketQueue::new()
+    }
+
+    #[inline]
+    fn insert(&mut self, state: State, cost: usize) {
+        self.enqueue(state, cost)
+    }
+
+    #[inline]
+    fn pop_min(&mut self) -> Option<State> {
+        self.dequeue_min()
+    }
+}
+
+struct MyOpenSet<Q> {
+    queue: Q,
     visited: VecSet<State, StateIndexer>,
 }
 
-impl MyOpenSet {
+impl<Q: StateQueue> MyOpenSet<Q> {
     fn new(state_indexer: StateIndexer) -> Self {
         Self {
-            queue: BucketQueue::new(),
+            queue: Q::new(),
             visited: VecSet::new(state_indexer),
         }
     }
 }
 
-impl OpenSet<State, Cost> for MyOpenSet {
+impl<C: CostT, Q: StateQueue> OpenSet<State, C> for MyOpenSet<Q> {
     #[inline]
-    fn insert(&mut self, state: State, cost: Cost) {
-        self.queue.push(state, cost as usize)
+    fn insert(&mut self, state: State, cost: C) {
+        self.queue.insert(state, cost.as_())
     }
 
     #[inline]
@@ -215,11 +253,11 @@ impl OpenSet<State, Cost> for MyOpenSet {
     }
 }
 
-struct MyCostMap {
-    map: VecMap<State, Cost, StateIndexer>,
+struct MyCostMap<C = Cost> {
+    map: VecMap<State, C, StateIndexer>,
 }
 
-impl MyCostMap {
+impl<C> MyCostMap<C> {
     fn new(state_indexer: StateIndexer) -> Self {
         Self {
             map: VecMap::new(state_indexer),
@@ -227,23 +265,35 @@ impl MyCostMap {
     }
 }
 
-impl CostMap<State, Cost> for MyCostMap {
-    fn get(&self, state: &State) -> Option<Cost> {
+impl<C: CostT> CostMap<State, C> for MyCostMap<C> {
+    fn get(&self, state: &State) -> Option<C> {
         self.map.get(state).copied()
     }
 
-    fn insert(&mut self, state: State, cost: Cost) -> bool {
-        match self.map.entry(&state) {
-            Some(prev_cost) if *prev_cost <= cost => false,
-            entry => {
-                *entry = Some(cost);
-                true
-            }
+    fn insert(&mut self, state: State, cost: C) -> bool {
+        let entry = self.map.entry(&state);
+        if entry.get().is_some_and(|prev_cost| *prev_cost <= cost) {
+            false
+        } else {
+            entry.insert(cost);
+            true
         }
     }
 }
 
 fn solve(input: &str, ultra: bool) -> Option<Cost> {
+    solve_with_tie_break(input, ultra, TieBreak::Lifo)
+}
+
+/// Picks the queue for the open set once, so the tie-break isn't checked on every pop.
+fn solve_with_tie_break<C: CostT>(input: &str, ultra: bool, tie_break: TieBreak) -> Option<C> {
+    match tie_break {
+        TieBreak::Fifo => solve_with_queue::<C, BucketQueue<VecDeque<State>>>(input, ultra),
+        TieBreak::Lifo => solve_with_queue::<C, BucketQueue<Vec<State>>>(input, ultra),
+    }
+}
+
+fn solve_with_queue<C: CostT, Q: StateQueue>(input: &str, ultra: bool) -> Option<C> {
     let grid = parse_input(input);
 
     let min_steps = if ultra { 4 } else { 1 };
@@ -265,7 +315,7 @@ fn solve(input: &str, ultra: bool) -> Option<Cost> {
     };
     shortest_path::a_star(
         problem,
-        MyOpenSet::new(state_indexer),
+        MyOpenSet::<Q>::new(state_indexer),
         MyCostMap::new(state_indexer),
     )
 }
@@ -293,4 +343,28 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(94));
     }
+
+    #[test]
+    fn test_tie_break() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        for tie_break in [TieBreak::Fifo, TieBreak::Lifo] {
+            assert_eq!(
+                solve_with_tie_break::<Cost>(&input, false, tie_break),
+                Some(102)
+            );
+            assert_eq!(
+                solve_with_tie_break::<Cost>(&input, true, tie_break),
+                Some(94)
+            );
+        }
+    }
+
+    #[test]
+    fn test_u32_cost() {
+        // A narrow grid forces a zig-zagging path whose cost doesn't fit in a u16
+        let row = "9".repeat(6000);
+        let input = format!("{row}\n{row}\n");
+        let result = solve_with_tie_break::<u32>(&input, false, TieBreak::Lifo);
+        assert!(result.is_some_and(|cost| cost > u16::MAX as u32));
+    }
 }
@@ -30,19 +30,26 @@ enum Target<'a> {
     Reject,
 }
 
-type Part = [u32; 4];
+type Part = [u32; NUM_PROPERTIES];
+
+/// The number of properties of a part: `x`, `m`, `a` and `s`.
+const NUM_PROPERTIES: usize = 4;
 
 fn parse_input(input: &str) -> IResult<&str, (Vec<(&str, Workflow)>, Vec<Part>)> {
     separated_pair(
-        separated_list1(
-            line_ending,
-            tuple((alpha1, delimited(tag("{"), parse_workflow, tag("}")))),
-        ),
+        parse_workflows,
         many1(line_ending),
         separated_list1(line_ending, delimited(tag("{"), parse_part, tag("}"))),
     )(input)
 }
 
+fn parse_workflows(input: &str) -> IResult<&str, Vec<(&str, Workflow<'_>)>> {
+    separated_list1(
+        line_ending,
+        tuple((alpha1, delimited(tag("{"), parse_workflow, tag("}")))),
+    )(input)
+}
+
 fn parse_workflow(input: &str) -> IResult<&str, Workflow> {
     let (input, rules) = separated_list1(
         tag(","),
@@ -117,35 +124,91 @@ fn is_part_accepted(part: Part, workflows: &HashMap<&str, Workflow>) -> bool {
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (_, (workflows, parts)) = parse_input(input).unwrap();
-    let workflows = HashMap::<&str, Workflow>::from_iter(workflows);
+/// Sums the ratings of the accepted parts, or returns `None` if no parts are accepted.
+#[cfg_attr(feature = "rayon", allow(dead_code))]
+fn sum_accepted_ratings(parts: &[Part], workflows: &HashMap<&str, Workflow>) -> Option<u32> {
     parts
-        .into_iter()
-        .filter(|part| is_part_accepted(*part, &workflows))
+        .iter()
+        .filter(|part| is_part_accepted(**part, workflows))
         .map(|part| part.iter().sum::<u32>())
         .sum1()
 }
 
+/// Parallel version of [`sum_accepted_ratings`]. Each part is checked independently, and the workflows are only read.
+#[cfg(feature = "rayon")]
+fn sum_accepted_ratings_par(parts: &[Part], workflows: &HashMap<&str, Workflow>) -> Option<u32> {
+    use rayon::prelude::*;
+
+    parts
+        .par_iter()
+        .filter(|part| is_part_accepted(**part, workflows))
+        .map(|part| part.iter().sum::<u32>())
+        .reduce_with(|a, b| a + b)
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    let (_, (workflows, parts)) = parse_input(input).unwrap();
+    let workflows = HashMap::<&str, Workflow>::from_iter(workflows);
+
+    #[cfg(feature = "rayon")]
+    return sum_accepted_ratings_par(&parts, &workflows);
+
+    #[cfg(not(feature = "rayon"))]
+    sum_accepted_ratings(&parts, &workflows)
+}
+
 #[derive(Copy, Clone, Debug)]
 struct Bound {
     gt: u32,
     lt: u32,
 }
 
-fn compute_accepted_combinations_for_bounds(bounds: [Bound; 4]) -> usize {
+/// Counts the parts within the bounds. Computed in `u64`, since the product of four spans of up to 4000 values
+/// doesn't fit in a 32-bit `usize`.
+fn compute_accepted_combinations_for_bounds<const P: usize>(bounds: [Bound; P]) -> u64 {
     bounds
         .iter()
-        .map(|Bound { gt, lt }| ((lt - 1) - (gt + 1) + 1) as usize)
-        .product::<usize>()
+        .map(|Bound { gt, lt }| ((lt - 1) - (gt + 1) + 1) as u64)
+        .product::<u64>()
+}
+
+/// Returns the number of properties the workflows' conditions refer to, i.e. one more than the highest property index.
+fn property_count(workflows: &HashMap<&str, Workflow>) -> usize {
+    workflows
+        .values()
+        .flat_map(|workflow| &workflow.rules)
+        .map(|(condition, _)| match condition {
+            Condition::Gt(property, _) | Condition::Lt(property, _) => property + 1,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Returns the disjoint regions of parts with `P` properties accepted by the workflows, starting from workflow `in`.
+fn accepted_regions<const P: usize>(workflows: &HashMap<&str, Workflow>) -> Vec<[Bound; P]> {
+    assert!(
+        property_count(workflows) <= P,
+        "workflows refer to more than {} properties",
+        P
+    );
+
+    let mut regions = vec![];
+    collect_accepted_regions(
+        workflows,
+        "in",
+        [Bound { gt: 0, lt: 4001 }; P],
+        &mut regions,
+    );
+    regions
 }
 
-fn compute_accepted_combinations(
+/// Pushes the disjoint regions within `bounds` that are accepted by workflow `label` to `regions`.
+fn collect_accepted_regions<const P: usize>(
     workflows: &HashMap<&str, Workflow>,
     label: &str,
-    mut bounds: [Bound; 4],
-) -> usize {
-    let mut result = 0;
+    mut bounds: [Bound; P],
+    regions: &mut Vec<[Bound; P]>,
+) {
     let workflow = workflows.get(label).unwrap();
     for (condition, target) in &workflow.rules {
         let mut rule_bounds = bounds;
@@ -169,26 +232,33 @@ fn compute_accepted_combinations(
             }
         }
 
-        result += match target {
-            Target::Workflow(label) => compute_accepted_combinations(workflows, label, rule_bounds),
-            Target::Accept => compute_accepted_combinations_for_bounds(rule_bounds),
-            Target::Reject => 0,
-        };
+        match target {
+            Target::Workflow(label) => {
+                collect_accepted_regions(workflows, label, rule_bounds, regions)
+            }
+            Target::Accept => regions.push(rule_bounds),
+            Target::Reject => {}
+        }
     }
 
-    result += match workflow.fallback {
-        Target::Workflow(label) => compute_accepted_combinations(workflows, label, bounds),
-        Target::Accept => compute_accepted_combinations_for_bounds(bounds),
-        Target::Reject => 0,
-    };
+    match workflow.fallback {
+        Target::Workflow(label) => collect_accepted_regions(workflows, label, bounds, regions),
+        Target::Accept => regions.push(bounds),
+        Target::Reject => {}
+    }
+}
 
-    result
+fn compute_accepted_combinations<const P: usize>(workflows: &HashMap<&str, Workflow>) -> u64 {
+    accepted_regions::<P>(workflows)
+        .into_iter()
+        .map(compute_accepted_combinations_for_bounds)
+        .sum()
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
+pub fn part_two(input: &str) -> Option<u64> {
     let (_, (workflows, _)) = parse_input(input).unwrap();
     let workflows = HashMap::<&str, Workflow>::from_iter(workflows);
-    compute_accepted_combinations(&workflows, "in", [Bound { gt: 0, lt: 4001 }; 4]).into()
+    compute_accepted_combinations::<NUM_PROPERTIES>(&workflows).into()
 }
 
 #[cfg(test)]
@@ -206,4 +276,61 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(167409079868000));
     }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_sum_accepted_ratings_par() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let (_, (workflows, parts)) = parse_input(&input).unwrap();
+        let workflows = HashMap::<&str, Workflow>::from_iter(workflows);
+
+        let result = sum_accepted_ratings_par(&parts, &workflows);
+        assert_eq!(result, Some(19114));
+        assert_eq!(result, sum_accepted_ratings(&parts, &workflows));
+    }
+
+    #[test]
+    fn test_accepted_regions() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        let (_, (workflows, _)) = parse_input(&input).unwrap();
+        let workflows = HashMap::<&str, Workflow>::from_iter(workflows);
+
+        let regions = accepted_regions::<NUM_PROPERTIES>(&workflows);
+        let volume = regions
+            .iter()
+            .map(|&bounds| compute_accepted_combinations_for_bounds(bounds))
+            .sum::<u64>();
+        assert_eq!(volume, 167409079868000);
+
+        // Regions are disjoint: every pair is separated along some property
+        for (a, b) in regions.iter().tuple_combinations() {
+            assert!(a
+                .iter()
+                .zip(b)
+                .any(|(a, b)| a.lt <= b.gt + 1 || b.lt <= a.gt + 1));
+        }
+    }
+
+    #[test]
+    fn test_accepted_combinations_for_bounds_u64() {
+        let full = [Bound { gt: 0, lt: 4001 }; NUM_PROPERTIES];
+        assert_eq!(
+            compute_accepted_combinations_for_bounds(full),
+            256_000_000_000_000
+        );
+        assert!(compute_accepted_combinations_for_bounds(full) > u32::MAX as u64);
+    }
+
+    #[test]
+    fn test_two_properties() {
+        let (_, workflows) = parse_workflows("in{x<2001:A,m>1000:qq,R}\nqq{m<3001:A,R}").unwrap();
+        let workflows = HashMap::<&str, Workflow>::from_iter(workflows);
+        assert_eq!(property_count(&workflows), 2);
+
+        // x in 1..=2000 with any m, plus x in 2001..=4000 with m in 1001..=3000
+        assert_eq!(
+            compute_accepted_combinations::<2>(&workflows),
+            2000 * 4000 + 2000 * 2000
+        );
+    }
 }
@@ -21,7 +21,7 @@ enum ModuleType {
     Conjunction,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct ModuleSpec<T> {
     label: T,
     module_type: ModuleType,
@@ -29,28 +29,34 @@ struct ModuleSpec<T> {
 }
 
 fn parse_input(input: &str) -> IResult<&str, Vec<ModuleSpec<String>>> {
-    separated_list1(
-        line_ending,
-        map(
-            separated_pair(
-                alt((
-                    map(tag("broadcaster"), |label| (label, ModuleType::Broadcast)),
-                    map(preceded(tag("%"), alpha1), |label| {
-                        (label, ModuleType::FlipFlop)
-                    }),
-                    map(preceded(tag("&"), alpha1), |label| {
-                        (label, ModuleType::Conjunction)
-                    }),
-                )),
-                tag(" -> "),
-                separated_list1(tag(", "), map(alpha1, str::to_string)),
-            ),
-            |((label, module_type), destinations)| ModuleSpec {
-                label: label.to_string(),
-                module_type,
-                destinations,
-            },
+    separated_list1(line_ending, parse_module)(input)
+}
+
+/// Parses the module specs line by line, without collecting them first.
+fn parse_module_iter(input: &str) -> impl Iterator<Item = ModuleSpec<String>> + '_ {
+    input.lines().map(|line| parse_module(line).unwrap().1)
+}
+
+fn parse_module(input: &str) -> IResult<&str, ModuleSpec<String>> {
+    map(
+        separated_pair(
+            alt((
+                map(tag("broadcaster"), |label| (label, ModuleType::Broadcast)),
+                map(preceded(tag("%"), alpha1), |label| {
+                    (label, ModuleType::FlipFlop)
+                }),
+                map(preceded(tag("&"), alpha1), |label| {
+                    (label, ModuleType::Conjunction)
+                }),
+            )),
+            tag(" -> "),
+            separated_list1(tag(", "), map(alpha1, str::to_string)),
         ),
+        |((label, module_type), destinations)| ModuleSpec {
+            label: label.to_string(),
+            module_type,
+            destinations,
+        },
     )(input)
 }
 
@@ -183,8 +189,9 @@ impl PulseReceiver for ConjunctionModule {
 }
 
 fn initialize_modules(
-    module_specs: Vec<ModuleSpec<String>>,
+    module_specs: impl IntoIterator<Item = ModuleSpec<String>>,
 ) -> (HashMap<String, usize>, Vec<Module>) {
+    let module_specs = module_specs.into_iter().collect_vec();
     let source_labels = module_specs
         .iter()
         .map(|spec| spec.label.clone())
@@ -266,8 +273,7 @@ fn initialize_modules(
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let (_, module_specs) = parse_input(input).unwrap();
-    let (label_to_id, mut modules) = initialize_modules(module_specs);
+    let (label_to_id, mut modules) = initialize_modules(parse_module_iter(input));
 
     let mut queue = VecDeque::new();
 
@@ -393,4 +399,13 @@ mod tests {
 
     #[test]
     fn test_part_two() {}
+
+    #[test]
+    fn test_parse_module_iter() {
+        for part in [1, 2] {
+            let input = advent_of_code::template::read_file_part("examples", DAY, part);
+            let (_, module_specs) = parse_input(&input).unwrap();
+            assert_eq!(parse_module_iter(&input).collect_vec(), module_specs);
+        }
+    }
 }
@@ -1,3 +1,4 @@
+use advent_of_code::util::linalg::gaussian_elimination;
 use advent_of_code::util::{VecSet, VecTable};
 
 advent_of_code::solution!(21);
@@ -5,47 +6,35 @@ advent_of_code::solution!(21);
 type CoordT = u32;
 type Coord = advent_of_code::util::coord::Coord<CoordT>;
 type CoordIndexer = advent_of_code::util::coord::CoordIndexer<CoordT>;
+type DiamondIndexer = advent_of_code::util::coord::DiamondIndexer<CoordT>;
 type Grid = VecTable<Coord, bool, CoordIndexer>;
 
+/// Parses the grid and the start coordinate. The start is marked by `S` if present, and otherwise
+/// assumed to be the center of the grid.
 fn parse_input(input: &str) -> (Grid, Coord) {
-    let mut width = None;
-    let data = input
+    let grid = Grid::from_grid_str(input, |c| c == '#').unwrap();
+    let start = input
         .lines()
-        .flat_map(|line| {
-            if width.is_none() {
-                width = Some(line.len());
-            } else {
-                debug_assert_eq!(width.unwrap(), line.len());
-            }
-            line.chars().map(|c| c == '#')
-        })
-        .collect::<Vec<_>>();
-    let width = width.unwrap();
-    let height = data.len() / width;
-    let indexer = CoordIndexer::new(width as CoordT, height as CoordT);
-    (
-        Grid::from_vec(data, indexer),
-        Coord::new(width as CoordT / 2, height as CoordT / 2),
-    )
+        .enumerate()
+        .find_map(|(y, line)| Some(Coord::new(line.find('S')? as CoordT, y as CoordT)));
+    let CoordIndexer { width, height } = *grid.indexer();
+    (grid, start.unwrap_or(Coord::new(width / 2, height / 2)))
 }
 
 fn count_reached_tiles(grid: &Grid, grid_center: Coord, steps: u32) -> Vec<u32> {
     // Indexer for the tiles reachable within the number of steps
-    // TODO: This is still wasteful, since we can actually only reach a diamond-shaped subset of the grid
-    let full_grid_indexer = CoordIndexer::new(2 * steps + 1, 2 * steps + 1);
+    let full_grid_indexer = DiamondIndexer::new(steps);
     let full_grid_center = Coord::new(steps, steps);
 
     let mut visited = VecSet::new(full_grid_indexer);
     let mut frontier = vec![full_grid_center];
     visited.insert(full_grid_center);
 
-    let to_grid_coord = |coord: Coord| -> Coord {
+    // Coordinate in the infinitely tiled grid, relative to the origin of the base grid
+    let to_tiled_coord = |coord: Coord| -> advent_of_code::util::coord::Coord<i32> {
         let x = (coord.x + grid_center.x) as i32 - full_grid_center.x as i32;
         let y = (coord.y + grid_center.y) as i32 - full_grid_center.y as i32;
-        Coord::new(
-            x.rem_euclid(grid.indexer().width as i32) as CoordT,
-            y.rem_euclid(grid.indexer().height as i32) as CoordT,
-        )
+        advent_of_code::util::coord::Coord::new(x, y)
     };
 
     let mut odd_reached = 1; // Start at 1 because the center is always reached
@@ -70,8 +59,8 @@ fn count_reached_tiles(grid: &Grid, grid_center: Coord, steps: u32) -> Vec<u32>
                     continue;
                 }
 
-                let grid_coord = to_grid_coord(neighbor);
-                if *grid.get(&grid_coord) {
+                let tiled_coord = to_tiled_coord(neighbor);
+                if *grid.get_wrapped(tiled_coord) {
                     continue;
                 }
 
@@ -93,43 +82,6 @@ fn count_reached_tiles(grid: &Grid, grid_center: Coord, steps: u32) -> Vec<u32>
     reached
 }
 
-fn gaussian_elimination<const N: usize, const M: usize>(mut matrix: [[f32; M]; N]) -> [f32; N] {
-    // TODO: Integer version of this algorithm
-
-    for i in 0..N {
-        // Find pivot for column i
-        let mut pivot_row = i;
-        for j in i + 1..N {
-            if matrix[j][i].abs() > matrix[pivot_row][i].abs() {
-                pivot_row = j;
-            }
-        }
-
-        // Swap rows i and pivot_row
-        matrix.swap(i, pivot_row);
-
-        // Eliminate column i for rows i+1..N
-        for j in i + 1..N {
-            let factor = matrix[j][i] / matrix[i][i];
-            for k in i..M {
-                matrix[j][k] -= factor * matrix[i][k];
-            }
-        }
-    }
-
-    // Back substitution
-    let mut x = [0.; N];
-    for i in (0..N).rev() {
-        x[i] = matrix[i][N];
-        for j in i + 1..N {
-            x[i] -= matrix[i][j] * x[j];
-        }
-        x[i] /= matrix[i][i];
-    }
-
-    x
-}
-
 fn solve_part_one(input: &str, steps: u32) -> Option<u32> {
     let (grid, start) = parse_input(input);
     count_reached_tiles(&grid, start, steps).last().copied()
@@ -191,7 +143,7 @@ pub fn part_two(input: &str) -> Option<usize> {
     ];
 
     // Gaussian elimination:
-    let [a1, b1, a2, b2] = gaussian_elimination(augmented_matrix);
+    let [a1, b1, a2, b2] = gaussian_elimination(augmented_matrix)?;
     let a1 = a1.round() as usize;
     let b1 = b1.round() as usize;
     let a2 = a2.round() as usize;
@@ -226,4 +178,19 @@ mod tests {
     fn test_part_two() {
         // The solution to part two makes assumptions about the real input, and so doesn't work for the example input.
     }
+
+    #[test]
+    fn test_parse_input_start() {
+        let (_, start) = parse_input("S....\n.....\n.....\n");
+        assert_eq!(start, Coord::new(0, 0));
+
+        let (_, start) = parse_input(".....\n.....\n.....\n");
+        assert_eq!(start, Coord::new(2, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "RaggedLine")]
+    fn test_parse_input_ragged() {
+        parse_input("S....\n....\n.....\n");
+    }
 }
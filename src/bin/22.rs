@@ -6,7 +6,10 @@ use nom::combinator::map_res;
 use nom::multi::separated_list1;
 use nom::sequence::{preceded, separated_pair};
 use nom::IResult;
+use petgraph::dot::Config;
+use petgraph::graph::DiGraph;
 
+use advent_of_code::util::graph::{self, print_graph, AdjList};
 use advent_of_code::util::{Indexer, LinearIndexer, VecSet, VecTable};
 
 advent_of_code::solution!(22);
@@ -58,6 +61,22 @@ fn get_floor_dimensions(bricks: &[Brick]) -> [CoordT; 2] {
 
 type AdjacencyList = VecTable<BrickIndex, Vec<BrickIndex>, LinearIndexer<BrickIndex>>;
 
+/// Removes duplicate neighbors, assuming that duplicates are contiguous. Cheaper than `dedup_unsorted`, but leaves
+/// non-contiguous duplicates in place.
+fn dedup_contiguous(neighbors: &mut Vec<BrickIndex>) {
+    neighbors.dedup();
+}
+
+/// Removes duplicate neighbors regardless of their order, by sorting the neighbors first.
+///
+/// Not needed by the solver, since the scan order keeps duplicates contiguous, so it's only used to check
+/// [`dedup_contiguous`] against.
+#[cfg(test)]
+fn dedup_unsorted(neighbors: &mut Vec<BrickIndex>) {
+    neighbors.sort_unstable();
+    neighbors.dedup();
+}
+
 /// Returns two adjacency lists:
 /// 1. Brick -> bricks supporting it
 /// 2. Brick -> bricks supported by it
@@ -114,7 +133,7 @@ fn build_supporting_graph(input: &str) -> (AdjacencyList, AdjacencyList) {
 
         // Remove duplicates. Note that we don't need to sort the list first, because bricks are contiguous,
         // and so any duplicates will be contiguous as well.
-        supported_by.dedup();
+        dedup_contiguous(supported_by);
 
         // Update inverse adjacency list
         for supported_by_brick in supported_by.iter() {
@@ -130,9 +149,31 @@ fn build_supporting_graph(input: &str) -> (AdjacencyList, AdjacencyList) {
         }
     }
 
+    // Not from the tests, which would overwrite the output for the actual input with the example's
+    if cfg!(feature = "debug_output") && !cfg!(test) {
+        print_graph(
+            &build_petgraph(&supporting)
+                .map(|node_index, _| node_index.index().to_string(), |_, _| ""),
+            "22",
+            "dot",
+            &[Config::EdgeNoLabel],
+        );
+    }
+
     (supported_by, supporting)
 }
 
+/// Converts the "brick -> bricks supported by it" adjacency list to a graph, where node `i` is brick `i`.
+fn build_petgraph(supporting: &AdjacencyList) -> DiGraph<(), ()> {
+    let mut adj = AdjList::new(supporting.indexer().len() as BrickIndex);
+    for (brick_index, supported_bricks) in supporting.iter() {
+        for supported_brick in supported_bricks {
+            adj.add_edge(brick_index, *supported_brick, ());
+        }
+    }
+    graph::from_adjacency(&adj)
+}
+
 pub fn part_one(input: &str) -> Option<usize> {
     let (supported_by, supporting) = build_supporting_graph(input);
     supporting
@@ -194,4 +235,24 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(7));
     }
+
+    #[test]
+    fn test_build_petgraph() {
+        let (_, supporting) =
+            build_supporting_graph(&advent_of_code::template::read_file("examples", DAY));
+        let graph = build_petgraph(&supporting);
+        assert_eq!(graph.node_count(), 7);
+        assert_eq!(graph.edge_count(), 9);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut neighbors = vec![3, 1, 1, 3, 2, 1];
+        dedup_contiguous(&mut neighbors);
+        assert_eq!(neighbors, [3, 1, 3, 2, 1]);
+
+        let mut neighbors = vec![3, 1, 1, 3, 2, 1];
+        dedup_unsorted(&mut neighbors);
+        assert_eq!(neighbors, [1, 2, 3]);
+    }
 }
@@ -7,14 +7,15 @@ use arrayvec::ArrayVec;
 use itertools::izip;
 use petgraph::visit::EdgeRef;
 
-use advent_of_code::util::{BitSet, Indexer, LinearIndexer, VecTable};
+use advent_of_code::util::graph::print_graph;
+use advent_of_code::util::{read_normalized, BitSet, Indexer, LinearIndexer, VecTable};
 
 use crate::tile_grid::Tile;
 
 advent_of_code::solution!(23);
 
 pub fn part_one(input: &str) -> Option<Cost> {
-    solve(input, false)
+    solve_part_one_dag(input)
 }
 
 pub fn part_two(input: &str) -> Option<Cost> {
@@ -78,6 +79,7 @@ fn solve(input: &str, part_two: bool) -> Option<Cost> {
 
     let mut cache = Cache::new(*adj_list.0.indexer());
     let compute_reachable = ComputeReachable::new(&adj_list);
+    let upper_bound = UpperBound::new(&adj_list);
     let target_preimage = adj_list.preimage(target_node);
 
     // Cannot push start node to stack here, because its index is out of bounds for the bitsets.
@@ -109,6 +111,12 @@ fn solve(input: &str, part_two: bool) -> Option<Cost> {
             continue;
         }
 
+        // Prune the path if it can't beat the longest path found so far, even if it were to take the longest outgoing
+        // edge of every reachable node.
+        if path_cost + upper_bound.remaining_cost(reachable) <= max_path_cost {
+            continue;
+        }
+
         // Prune the path if we've already found a path to this node that can still reach the same set (or a superset!)
         // of nodes at a better cost.
         if !cache.insert_if_max(node, reachable, path_cost) {
@@ -125,11 +133,63 @@ fn solve(input: &str, part_two: bool) -> Option<Cost> {
         );
     }
 
+    if cfg!(feature = "debug_output") {
+        println!("Cache entries: {}", cache.len());
+    }
+
     Some(max_path_cost)
 }
 
+/// Solves part one as a longest path problem on a DAG, since the slopes make the trails one-way and acyclic. Processes
+/// the nodes in topological order (using Kahn's algorithm), so every node's longest path is final before its outgoing
+/// edges are relaxed.
+///
+/// Returns `None` if the trails contain a cycle after all.
+fn solve_part_one_dag(input: &str) -> Option<Cost> {
+    let (adj_list, start_node, target_node) = build_trails_map(input, false);
+    let num_nodes = adj_list.len() as usize;
+
+    let mut in_degree = vec![0; num_nodes];
+    for node in 0..adj_list.len() {
+        for &(next_node, _) in adj_list.get(node) {
+            in_degree[next_node as usize] += 1;
+        }
+    }
+
+    let mut queue = (0..adj_list.len())
+        .filter(|&node| in_degree[node as usize] == 0)
+        .collect::<Vec<_>>();
+    let mut longest_path = vec![None; num_nodes];
+    longest_path[start_node as usize] = Some(0);
+    let mut num_processed = 0;
+
+    while let Some(node) = queue.pop() {
+        num_processed += 1;
+
+        for &(next_node, cost) in adj_list.get(node) {
+            if let Some(path_cost) = longest_path[node as usize] {
+                longest_path[next_node as usize] =
+                    longest_path[next_node as usize].max(Some(path_cost + cost));
+            }
+
+            in_degree[next_node as usize] -= 1;
+            if in_degree[next_node as usize] == 0 {
+                queue.push(next_node);
+            }
+        }
+    }
+
+    if num_processed < num_nodes {
+        // Not all nodes could be ordered, so there is a cycle
+        return None;
+    }
+
+    longest_path[target_node as usize]
+}
+
 fn build_trails_map(input: &str, part_two: bool) -> (AdjacencyList, NodeIndex, NodeIndex) {
-    let tile_grid = tile_grid::TileGrid::new(input);
+    let input = read_normalized(input);
+    let tile_grid = tile_grid::TileGrid::new(&input);
 
     // Start coord is the only path tile in the top row
     let start_coord = (0..tile_grid.width())
@@ -147,28 +207,30 @@ fn build_trails_map(input: &str, part_two: bool) -> (AdjacencyList, NodeIndex, N
 
     let part = if part_two { 2 } else { 1 };
     let engine = if part_two { "neato" } else { "dot" };
-    if cfg!(feature = "debug_output") {
-        graph::print_graph(
+    if cfg!(feature = "debug_output") && !cfg!(test) {
+        print_graph(
             &graph.clone().into_graph().map(
                 |_, coord| format!("({}, {})", coord.x, coord.y),
                 |_, cost| *cost,
             ),
             &format!("23-{}", part),
             engine,
+            &[],
         );
     }
 
     let (graph, start_node, target_node) =
         graph::optimize_graph(graph, start_coord, target_coord, part_two);
 
-    if cfg!(feature = "debug_output") {
-        graph::print_graph(
+    if cfg!(feature = "debug_output") && !cfg!(test) {
+        print_graph(
             &graph.map(
                 |node_index, _| format!("{}", node_index.index()),
                 |_, cost| *cost,
             ),
             &format!("23-{}-opt", part),
             engine,
+            &[],
         );
     }
 
@@ -221,22 +283,24 @@ impl AdjacencyList {
     /// Returns the set of internal nodes that have an incoming edge from `node`.
     #[inline]
     fn image(&self, node: NodeIndex) -> u32 {
-        self.0[node]
-            .iter()
-            .filter(|(node, _)| self.is_internal(*node))
-            .map(|(node, _)| 1 << node)
-            .fold(0, |a, b| a | b)
+        u32::from_indices(
+            self.0[node]
+                .iter()
+                .map(|&(node, _)| node)
+                .filter(|&node| self.is_internal(node)),
+        )
     }
 
     /// Returns the set of internal nodes that have an outgoing edge to `node`.
     #[inline]
     fn preimage(&self, node: NodeIndex) -> u32 {
-        self.0
-            .iter()
-            .filter(|(_, neighbors)| neighbors.iter().any(|(neighbor, _)| *neighbor == node))
-            .filter(|(node, _)| self.is_internal(*node))
-            .map(|(node, _)| 1 << node)
-            .fold(0, |a, b| a | b)
+        u32::from_indices(
+            self.0
+                .iter()
+                .filter(|(_, neighbors)| neighbors.iter().any(|(neighbor, _)| *neighbor == node))
+                .map(|(node, _)| node)
+                .filter(|&node| self.is_internal(node)),
+        )
     }
 }
 
@@ -331,30 +395,24 @@ mod graph {
                     break coord;
                 }
 
-                let neighbors = [
-                    Direction::Up,
-                    Direction::Down,
-                    Direction::Right,
-                    Direction::Left,
-                ]
-                .into_iter()
-                .filter(|direction| {
-                    // Don't backtrack
-                    *direction != prev_direction.opposite()
-                })
-                .filter_map(|direction| {
-                    let next_coord = coord.step(direction);
-                    match tile_grid.get(next_coord) {
-                        Some(Tile::Path) => Some((next_coord, direction)),
-                        Some(Tile::Slope(slope_direction))
-                            if slope_direction == direction || part_two =>
-                        {
-                            Some((next_coord, direction))
+                let neighbors = Direction::all()
+                    .filter(|direction| {
+                        // Don't backtrack
+                        *direction != prev_direction.opposite()
+                    })
+                    .filter_map(|direction| {
+                        let next_coord = coord.step(direction);
+                        match tile_grid.get(next_coord) {
+                            Some(Tile::Path) => Some((next_coord, direction)),
+                            Some(Tile::Slope(slope_direction))
+                                if slope_direction == direction || part_two =>
+                            {
+                                Some((next_coord, direction))
+                            }
+                            _ => None,
                         }
-                        _ => None,
-                    }
-                })
-                .collect::<ArrayVec<_, 4>>();
+                    })
+                    .collect::<ArrayVec<_, 4>>();
 
                 match neighbors.len() {
                     0 => unreachable!("Invalid trail"),
@@ -389,33 +447,36 @@ mod graph {
         graph
     }
 
-    fn merge_into_single_neighbor(graph: &mut DiGraphMap<Coord, Cost>, node: Coord) -> Coord {
-        let neighbors = graph.neighbors(node).collect::<Vec<_>>();
-        assert_eq!(neighbors.len(), 1);
-
-        let neighbor = neighbors[0];
+    /// Merges `node` into its single neighbor, adding the cost of the edge between them to the neighbor's other
+    /// edges, and returns the neighbor.
+    ///
+    /// Returns `None` (leaving the graph unchanged) if `node` does not have exactly one neighbor, or is not connected
+    /// to it in both directions.
+    pub fn try_merge_degree1(graph: &mut DiGraphMap<Coord, Cost>, node: Coord) -> Option<Coord> {
+        let neighbor = graph.neighbors(node).exactly_one().ok()?;
+        if !graph.contains_edge(neighbor, node) {
+            return None;
+        }
 
         // Remove edges between node and neighbor
-        graph.remove_edge(node, neighbor).unwrap();
-        let removed_cost = graph.remove_edge(neighbor, node).unwrap();
+        graph.remove_edge(node, neighbor)?;
+        let removed_cost = graph.remove_edge(neighbor, node)?;
 
         // Add removed cost to that of neighbor's other edges
         let neighbor_neighbors = graph.neighbors(neighbor).collect::<Vec<_>>();
         for neighbor_neighbor in neighbor_neighbors {
-            graph
-                .edge_weight_mut(neighbor, neighbor_neighbor)
-                .map(|cost| *cost += removed_cost)
-                .unwrap();
-            graph
-                .edge_weight_mut(neighbor_neighbor, neighbor)
-                .map(|cost| *cost += removed_cost)
-                .unwrap();
+            if let Some(cost) = graph.edge_weight_mut(neighbor, neighbor_neighbor) {
+                *cost += removed_cost;
+            }
+            if let Some(cost) = graph.edge_weight_mut(neighbor_neighbor, neighbor) {
+                *cost += removed_cost;
+            }
         }
 
         // Remove node
         graph.remove_node(node);
 
-        neighbor
+        Some(neighbor)
     }
 
     fn remove_incoming_edges(graph: &mut DiGraphMap<Coord, Cost>, node: Coord) {
@@ -451,7 +512,7 @@ mod graph {
     ) -> (DiGraph<(), Cost>, NodeIndex, NodeIndex) {
         let start_coord = if part_two {
             // Merge start node into the first intersection node
-            merge_into_single_neighbor(&mut graph, start_coord)
+            try_merge_degree1(&mut graph, start_coord).unwrap_or(start_coord)
         } else {
             start_coord
         };
@@ -460,7 +521,7 @@ mod graph {
 
         let target_coord = if part_two {
             // Merge target node into the last intersection node
-            merge_into_single_neighbor(&mut graph, target_coord)
+            try_merge_degree1(&mut graph, target_coord).unwrap_or(target_coord)
         } else {
             target_coord
         };
@@ -520,25 +581,6 @@ mod graph {
 
         (graph, start_node, target_node)
     }
-
-    pub fn print_graph(graph: &DiGraph<String, Cost>, name: &str, engine: &str) {
-        use std::io::Write;
-
-        let mut file = std::fs::File::create(format!("data/viz/{}.dot", name)).unwrap();
-        write!(file, "{}", petgraph::dot::Dot::new(&graph)).unwrap();
-
-        // Run dot to generate SVG
-        std::process::Command::new("dot")
-            .args([
-                "-Tsvg",
-                format!("data/viz/{}.dot", name).as_str(),
-                "-o",
-                format!("data/viz/{}.svg", name).as_str(),
-                format!("-K{}", engine).as_str(),
-            ])
-            .output()
-            .unwrap();
-    }
 }
 
 struct Cache {
@@ -552,6 +594,11 @@ impl Cache {
         }
     }
 
+    /// Returns the total number of (node, bitset)-value pairs stored in the cache.
+    fn len(&self) -> usize {
+        self.cache.values().map(|(bitsets, _)| bitsets.len()).sum()
+    }
+
     /// Inserts a new (node, bitset)-value pair into the cache if a pair with a superset bitset and a higher value is
     /// not already present.
     ///
@@ -643,6 +690,39 @@ impl ComputeReachable {
         }
     }
 }
+
+struct UpperBound {
+    /// `max_outgoing[i]` is the cost of the longest edge outgoing from node `i`.
+    max_outgoing: u32x32,
+}
+
+impl UpperBound {
+    fn new(adj_list: &AdjacencyList) -> Self {
+        let mut max_outgoing = [0; 32];
+        for node in 0..adj_list.len() - 2 {
+            max_outgoing[node as usize] = adj_list
+                .get(node)
+                .iter()
+                .map(|&(_, cost)| cost)
+                .max()
+                .unwrap_or(0);
+        }
+        let max_outgoing = u32x32::from_array(max_outgoing);
+
+        UpperBound { max_outgoing }
+    }
+
+    /// Returns an upper bound on the cost of the remainder of a path that can only visit the nodes in `reachable`.
+    ///
+    /// Every node on the remainder of the path is left through one of its outgoing edges, so the sum of the longest
+    /// outgoing edge of each reachable node is never less than the actual remaining cost.
+    fn remaining_cost(&self, reachable: u32) -> Cost {
+        mask32x32::from_bitmask(reachable as u64)
+            .select(self.max_outgoing, u32x32::splat(0))
+            .reduce_sum()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -653,9 +733,85 @@ mod tests {
         assert_eq!(result, Some(94));
     }
 
+    #[test]
+    fn test_solve_part_one_dag() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        assert_eq!(solve_part_one_dag(&input), Some(94));
+        assert_eq!(solve_part_one_dag(&input), solve(&input, false));
+    }
+
     #[test]
     fn test_part_two() {
         let result = solve(&advent_of_code::template::read_file("examples", DAY), true);
         assert_eq!(result, Some(154));
     }
+
+    #[test]
+    fn test_cache_len() {
+        let mut cache = Cache::new(LinearIndexer::new(2));
+
+        assert!(cache.insert_if_max(0, 0b011, 10));
+        assert!(cache.insert_if_max(0, 0b110, 10));
+        assert!(cache.insert_if_max(0, 0b001, 20));
+        assert!(cache.insert_if_max(1, 0b011, 5));
+        // Dominated by (0b011, 10)
+        assert!(!cache.insert_if_max(0, 0b010, 10));
+
+        assert_eq!(cache.len(), 4);
+    }
+
+    #[test]
+    fn test_try_merge_degree1() {
+        use petgraph::graphmap::DiGraphMap;
+
+        let a = Coord::new(0, 0);
+        let b = Coord::new(1, 0);
+        let c = Coord::new(2, 0);
+        let d = Coord::new(3, 0);
+
+        let mut graph = DiGraphMap::<Coord, Cost>::new();
+        for (from, to, cost) in [(a, b, 1), (b, c, 2), (b, d, 3)] {
+            graph.add_edge(from, to, cost);
+            graph.add_edge(to, from, cost);
+        }
+
+        // `b` has three neighbors, so it can't be merged
+        assert_eq!(graph::try_merge_degree1(&mut graph, b), None);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 6);
+
+        assert_eq!(graph::try_merge_degree1(&mut graph, a), Some(b));
+        assert!(!graph.contains_node(a));
+        assert_eq!(graph.edge_weight(b, c), Some(&3));
+        assert_eq!(graph.edge_weight(c, b), Some(&3));
+        assert_eq!(graph.edge_weight(d, b), Some(&4));
+
+        // `c` only has an outgoing edge to `b` once the edge back is removed
+        graph.remove_edge(b, c);
+        assert_eq!(graph::try_merge_degree1(&mut graph, c), None);
+        assert!(graph.contains_node(c));
+    }
+
+    #[test]
+    fn test_upper_bound() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        for (part_two, expected) in [(false, 94), (true, 154)] {
+            let (adj_list, start_node, _) = build_trails_map(&input, part_two);
+            let compute_reachable = ComputeReachable::new(&adj_list);
+            let upper_bound = UpperBound::new(&adj_list);
+
+            // The bound from the start node must not prune the longest path
+            let bound = adj_list
+                .get(start_node)
+                .iter()
+                .map(|&(node, cost)| {
+                    cost + upper_bound.remaining_cost(compute_reachable.compute_reachable(node, &0))
+                })
+                .max()
+                .unwrap();
+            assert!(bound >= expected);
+
+            assert_eq!(solve(&input, part_two), Some(expected));
+        }
+    }
 }
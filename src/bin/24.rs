@@ -2,11 +2,14 @@
 
 use std::simd::prelude::*;
 
+use advent_of_code::util::linalg::gaussian_elimination;
+use advent_of_code::util::parse::coord3;
 use nalgebra::{Matrix2, Vector2};
-use nom::bytes::complete::tag;
-use nom::character::complete::{char, i64, space1};
-use nom::combinator::map;
-use nom::sequence::{delimited, preceded, separated_pair, tuple};
+use nom::branch::alt;
+use nom::character::complete::{char, i128, one_of, space1};
+use nom::combinator::{map, not};
+use nom::number::complete::double;
+use nom::sequence::{delimited, separated_pair, terminated};
 use nom::IResult;
 use num::Zero;
 
@@ -25,24 +28,27 @@ fn parse_input_iter(input: &str) -> impl Iterator<Item = ([f64; 3], [f64; 3])> +
 }
 
 fn parse_vector(input: &str) -> IResult<&str, [f64; 3]> {
-    map(
-        tuple((
-            parse_scalar,
-            preceded(tag(", "), parse_scalar),
-            preceded(tag(", "), parse_scalar),
-        )),
-        |(x, y, z)| [x, y, z],
-    )(input)
+    coord3(parse_scalar)(input)
 }
 
+/// Parses an integer (up to the range of `i128`), falling back to parsing a floating point number.
 fn parse_scalar(input: &str) -> IResult<&str, f64> {
-    map(i64, |i| i as f64)(input)
+    alt((
+        map(terminated(i128, not(one_of(".eE"))), |i| i as f64),
+        parse_f64,
+    ))(input)
+}
+
+fn parse_f64(input: &str) -> IResult<&str, f64> {
+    double(input)
 }
 
 type Scalar = f64;
 const LANES: usize = 8;
 
-fn solve_part_one(input: &str, min_pos: Scalar, max_pos: Scalar) -> Option<usize> {
+/// Counts the pairs of hailstones whose future paths cross (ignoring the z-axis) inside the test area, where both
+/// the x and y coordinates lie in `min_pos..=max_pos`.
+pub fn part_one_with_bounds(input: &str, min_pos: Scalar, max_pos: Scalar) -> Option<usize> {
     let hailstones = parse_input_iter(input).collect::<Vec<_>>();
 
     let min_pos = Simd::splat(min_pos);
@@ -149,49 +155,12 @@ fn solve_part_one(input: &str, min_pos: Scalar, max_pos: Scalar) -> Option<usize
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    solve_part_one(input, 200_000_000_000_000., 400_000_000_000_000.)
+    part_one_with_bounds(input, 200_000_000_000_000., 400_000_000_000_000.)
 }
 
-fn gaussian_elimination<const N: usize, const M: usize>(mut matrix: [[f64; M]; N]) -> [f64; N] {
-    // TODO: Integer version of this algorithm
-
-    for i in 0..N {
-        // Find pivot for column i
-        let mut pivot_row = i;
-        for j in i + 1..N {
-            if matrix[j][i].abs() > matrix[pivot_row][i].abs() {
-                pivot_row = j;
-            }
-        }
-
-        // Swap rows i and pivot_row
-        matrix.swap(i, pivot_row);
-
-        // Eliminate column i for rows i+1..N
-        for j in i + 1..N {
-            let factor = matrix[j][i] / matrix[i][i];
-            for k in i..M {
-                matrix[j][k] -= factor * matrix[i][k];
-            }
-        }
-    }
-
-    // Back substitution
-    let mut x = [0.; N];
-    for i in (0..N).rev() {
-        x[i] = matrix[i][N];
-        for j in i + 1..N {
-            x[i] -= matrix[i][j] * x[j];
-        }
-        x[i] /= matrix[i][i];
-    }
-
-    x
-}
-
-pub fn part_two(input: &str) -> Option<usize> {
-    let hailstones = parse_input_iter(input).take(3).collect::<Vec<_>>();
-
+/// Finds the position and velocity of the rock that hits all three hailstones, as `[pos.x, pos.y, pos.z, vel.x, vel.y,
+/// vel.z]`. Returns `None` if the resulting system of equations is singular.
+fn solve_rock(hailstones: [([f64; 3], [f64; 3]); 3]) -> Option<[f64; 6]> {
     // Find (pos, vel) such that for every (pos_i, vel_i) in hailstones there exists a t_i such that:
     // pos + vel * t_i = pos_i + vel_i * t_i
 
@@ -220,12 +189,7 @@ pub fn part_two(input: &str) -> Option<usize> {
     // Do the same for all three equations for i set to both (0, 1) and (0, 2), and solve the resulting system of linear
     // equations. Note that we have 6 equations and 6 unknowns, so we can use Gaussian elimination to solve the system.
 
-    let p0 = hailstones[0].0;
-    let v0 = hailstones[0].1;
-    let p1 = hailstones[1].0;
-    let v1 = hailstones[1].1;
-    let p2 = hailstones[2].0;
-    let v2 = hailstones[2].1;
+    let [(p0, v0), (p1, v1), (p2, v2)] = hailstones;
 
     // Augmented matrix containing coefficients of: pos.x, pos.y, pos.z, vel.x, vel.y, vel.z, constant
     let matrix = [
@@ -285,7 +249,17 @@ pub fn part_two(input: &str) -> Option<usize> {
         ],
     ];
 
-    let result = gaussian_elimination(matrix);
+    gaussian_elimination(matrix)
+}
+
+pub fn part_two(input: &str) -> Option<usize> {
+    let hailstones = parse_input_iter(input).collect::<Vec<_>>();
+
+    // Some triples of hailstones result in a singular system (e.g. if two of them move in parallel), so try consecutive
+    // triples until one can be solved.
+    let result = hailstones
+        .windows(3)
+        .find_map(|triple| solve_rock([triple[0], triple[1], triple[2]]))?;
     let x = result[0].round() as usize;
     let y = result[1].round() as usize;
     let z = result[2].round() as usize;
@@ -299,7 +273,7 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = solve_part_one(
+        let result = part_one_with_bounds(
             &advent_of_code::template::read_file("examples", DAY),
             7.,
             27.,
@@ -307,6 +281,13 @@ mod tests {
         assert_eq!(result, Some(2));
     }
 
+    #[test]
+    fn test_parse_large_and_fractional() {
+        let line = "100000000000000000000, -19.5, 3 @ -2, 1e3, -1";
+        let hailstones = parse_input_iter(line).collect::<Vec<_>>();
+        assert_eq!(hailstones, [([1e20, -19.5, 3.], [-2., 1000., -1.])]);
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
@@ -1,16 +1,14 @@
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, VecDeque};
 
-use nom::bytes::complete::tag;
+use advent_of_code::util::parse::labeled_list;
 use nom::character::complete::alpha1;
-use nom::multi::separated_list1;
-use nom::sequence::separated_pair;
 use nom::IResult;
 
 advent_of_code::solution!(25);
 
 fn parse_line(line: &str) -> IResult<&str, (&str, Vec<&str>)> {
-    separated_pair(alpha1, tag(": "), separated_list1(tag(" "), alpha1))(line)
+    labeled_list(alpha1, alpha1)(line)
 }
 
 fn remove_edge(adjacency_list: &mut [Vec<usize>], edge: (usize, usize)) {
@@ -125,7 +123,16 @@ fn find_connected_component_size(adjacency_list: &[Vec<usize>], node: usize) ->
     count
 }
 
+/// Upper bound on the number of path searches `part_one` performs before giving up.
+const MAX_PATH_SEARCHES: usize = 1_000_000;
+
 pub fn part_one(input: &str) -> Option<usize> {
+    solve_part_one(input, MAX_PATH_SEARCHES)
+}
+
+/// Find the sizes of the two groups separated by a 3-edge cut, and return their product. Returns `None` if no such
+/// cut is found within `max_path_searches` path searches, or if the graph doesn't have one.
+fn solve_part_one(input: &str, max_path_searches: usize) -> Option<usize> {
     let (mut adjacency_list, mut forward_edges) = {
         let mut name_to_index = HashMap::<&str, usize>::new();
         let mut adjacency_list = vec![];
@@ -156,6 +163,18 @@ pub fn part_one(input: &str) -> Option<usize> {
         usize::MAX - (adjacency_list[edge.0].len() + adjacency_list[edge.1].len())
     });
 
+    let mut path_searches = 0;
+    let mut find_path_within_budget =
+        |adjacency_list: &[Vec<usize>], start_node, end_node, heuristic: &[usize]| {
+            path_searches += 1;
+            if path_searches > max_path_searches {
+                return Err(());
+            }
+            Ok(find_path(adjacency_list, start_node, end_node, heuristic))
+        };
+
+    let mut found_cut = false;
+
     'outer: for edge_i in forward_edges {
         remove_edge(&mut adjacency_list, edge_i);
 
@@ -163,20 +182,36 @@ pub fn part_one(input: &str) -> Option<usize> {
         let heuristic = distances_to_all_nodes(&adjacency_list, edge_i.1);
 
         // Find path from edge_i.0 to edge_i.1
-        let path_j = find_path(&adjacency_list, edge_i.0, edge_i.1, &heuristic).unwrap();
+        let Some(path_j) =
+            find_path_within_budget(&adjacency_list, edge_i.0, edge_i.1, &heuristic).ok()?
+        else {
+            // edge_i is a bridge by itself, so this isn't a 3-edge cut
+            insert_edge(&mut adjacency_list, edge_i);
+            continue;
+        };
 
         for edge_j in path_j {
             remove_edge(&mut adjacency_list, edge_j);
 
             // Find path from edge_i.0 to edge_i.1
-            let path_k = find_path(&adjacency_list, edge_i.0, edge_i.1, &heuristic).unwrap();
+            let Some(path_k) =
+                find_path_within_budget(&adjacency_list, edge_i.0, edge_i.1, &heuristic).ok()?
+            else {
+                // edge_i and edge_j already form a cut, so this isn't a 3-edge cut
+                insert_edge(&mut adjacency_list, edge_j);
+                continue;
+            };
 
             // If there is a bridge, it must be along this path
             for edge_k in path_k {
                 remove_edge(&mut adjacency_list, edge_k);
 
-                if find_path(&adjacency_list, edge_i.0, edge_i.1, &heuristic).is_none() {
+                if find_path_within_budget(&adjacency_list, edge_i.0, edge_i.1, &heuristic)
+                    .ok()?
+                    .is_none()
+                {
                     // No alternative path from edge_i.0 to edge_i.1, so edge_k must've been a bridge
+                    found_cut = true;
                     break 'outer;
                 }
 
@@ -189,6 +224,10 @@ pub fn part_one(input: &str) -> Option<usize> {
         insert_edge(&mut adjacency_list, edge_i);
     }
 
+    if !found_cut {
+        return None;
+    }
+
     let group_size1 = find_connected_component_size(&adjacency_list, 0);
     let group_size2 = adjacency_list.len() - group_size1;
 
@@ -209,6 +248,19 @@ mod tests {
         assert_eq!(result, Some(54));
     }
 
+    #[test]
+    fn test_part_one_no_cut() {
+        // Complete graph on 5 nodes: every cut has at least 4 edges
+        let input = "a: b c d e\nb: c d e\nc: d e\nd: e\n";
+        assert_eq!(part_one(input), None);
+    }
+
+    #[test]
+    fn test_part_one_budget() {
+        let input = advent_of_code::template::read_file("examples", DAY);
+        assert_eq!(solve_part_one(&input, 1), None);
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
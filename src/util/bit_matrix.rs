@@ -1,5 +1,7 @@
 use elain::{Align, Alignment};
-use std::simd::{LaneCount, Simd, SupportedLaneCount};
+use std::ops::Range;
+use std::simd::num::SimdUint;
+use std::simd::Simd;
 
 /// A 2D matrix of bits, with a fixed size of N x N bytes.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -142,12 +144,100 @@ where
             rows
         }
     }
+
+    /// Copy the bytes of the given rows, e.g. to use them as a hash map key.
+    pub fn rows_bytes(&self, rows: Range<usize>) -> BitMatrixBytes {
+        BitMatrixBytes(self.bytes()[rows.start * N..rows.end * N].into())
+    }
+
+    /// Serialize the matrix as a string of `0`s and `1`s, row by row, without separators.
+    pub fn to_bitstring(&self) -> String {
+        (0..N * 8)
+            .flat_map(|i| (0..N * 8).map(move |j| (i, j)))
+            .map(|(i, j)| if self.get(i, j) { '1' } else { '0' })
+            .collect()
+    }
+
+    /// Deserialize a matrix from a string produced by [`BitMatrix::to_bitstring`].
+    ///
+    /// Returns `None` if the string has the wrong length or contains other characters than `0` and `1`.
+    pub fn from_bitstring(bitstring: &str) -> Option<Self> {
+        let size = N * 8;
+        if bitstring.len() != size * size {
+            return None;
+        }
+
+        let mut result = Self::new();
+        for (index, c) in bitstring.chars().enumerate() {
+            match c {
+                '0' => {}
+                '1' => result.set(index / size, index % size),
+                _ => return None,
+            }
+        }
+        Some(result)
+    }
+
+    /// Get row `i` as an integer, where column `j` maps to bit `63 - j`. Only valid for `N <= 8`.
+    pub fn row_as_u64(&self, i: usize) -> u64 {
+        assert!(N <= 8);
+        let mut bytes = [0; 8];
+        bytes[..N].copy_from_slice(&self.rows()[i]);
+        u64::from_be_bytes(bytes)
+    }
+
+    /// Set row `i` from an integer, where column `j` maps to bit `63 - j`. Only valid for `N <= 8`.
+    pub fn set_row_u64(&mut self, i: usize, bits: u64) {
+        assert!(N <= 8);
+        let bytes = bits.to_be_bytes();
+        self.rows_mut()[i].copy_from_slice(&bytes[..N]);
+    }
+
+    /// Get column `j` as an integer, where row `i` maps to bit `63 - i`. Only valid for `N <= 8`.
+    pub fn column_bits(&self, j: usize) -> u64 {
+        assert!(N <= 8);
+        (0..N * 8).fold(0, |bits, i| bits | (self.get(i, j) as u64) << (63 - i))
+    }
+
+    /// Count the number of set bits among the (up to) eight neighbors of position (i, j).
+    /// Positions outside the matrix are treated as unset.
+    pub fn count_neighbors(&self, i: usize, j: usize) -> u8 {
+        let size = N * 8;
+        let mut count = 0;
+        for ni in i.saturating_sub(1)..=usize::min(i + 1, size - 1) {
+            for nj in j.saturating_sub(1)..=usize::min(j + 1, size - 1) {
+                if (ni, nj) != (i, j) && self.get(ni, nj) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Compute the next generation of a Game-of-Life-style cellular automaton.
+    ///
+    /// An unset bit becomes set if its neighbor count is in `birth`, and a set bit stays set if its
+    /// neighbor count is in `survive`.
+    pub fn step_life(&self, birth: &[u8], survive: &[u8]) -> Self {
+        let mut result = Self::new();
+
+        for i in 0..N * 8 {
+            for j in 0..N * 8 {
+                let neighbors = self.count_neighbors(i, j);
+                let rule = if self.get(i, j) { survive } else { birth };
+                if rule.contains(&neighbors) {
+                    result.set(i, j);
+                }
+            }
+        }
+
+        result
+    }
 }
 
 impl<const N: usize> BitMatrix<N>
 where
     Align<N>: Alignment,
-    LaneCount<N>: SupportedLaneCount,
 {
     pub fn rows_simd(&self) -> &[Simd<u8, N>] {
         let (prefix, rows, suffix) = self.bytes().as_simd();
@@ -162,8 +252,28 @@ where
         assert!(suffix.is_empty());
         rows
     }
+
+    /// Count the number of set bits in row `i`.
+    pub fn row_count_ones(&self, i: usize) -> u32 {
+        self.rows_simd()[i].count_ones().cast::<u16>().reduce_sum() as u32
+    }
+
+    /// Count the number of set bits in the whole matrix.
+    pub fn count_ones(&self) -> u32 {
+        self.rows_simd()
+            .iter()
+            .fold(Simd::<u16, N>::splat(0), |acc, row| {
+                acc + row.count_ones().cast::<u16>()
+            })
+            .cast::<u32>()
+            .reduce_sum()
+    }
 }
 
+/// An owned copy of (some of) the bytes of a [`BitMatrix`], which can be hashed and compared cheaply.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitMatrixBytes(Box<[u8]>);
+
 impl<const N: usize> Default for BitMatrix<N>
 where
     Align<N>: Alignment,
@@ -172,3 +282,81 @@ where
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_life_blinker() {
+        let mut horizontal = BitMatrix::<1>::new();
+        horizontal.set(2, 1);
+        horizontal.set(2, 2);
+        horizontal.set(2, 3);
+
+        let mut vertical = BitMatrix::<1>::new();
+        vertical.set(1, 2);
+        vertical.set(2, 2);
+        vertical.set(3, 2);
+
+        assert_eq!(horizontal.count_neighbors(2, 2), 2);
+        assert_eq!(horizontal.count_neighbors(1, 2), 3);
+        assert_eq!(horizontal.count_neighbors(0, 0), 0);
+
+        let next = horizontal.step_life(&[3], &[2, 3]);
+        assert_eq!(next, vertical);
+        assert_eq!(next.step_life(&[3], &[2, 3]), horizontal);
+    }
+
+    #[test]
+    fn test_row_u64() {
+        let mut matrix = BitMatrix::<2>::new();
+        let bits = (1 << 63) | (1 << (63 - 5)) | (1 << (63 - 9));
+        matrix.set_row_u64(3, bits);
+
+        for j in 0..16 {
+            assert_eq!(matrix.get(3, j), matches!(j, 0 | 5 | 9));
+            assert!(!matrix.get(2, j));
+        }
+        assert_eq!(matrix.row_as_u64(3), bits);
+    }
+
+    #[test]
+    fn test_column_bits() {
+        let mut matrix = BitMatrix::<2>::new();
+        matrix.set(0, 3);
+        matrix.set(5, 3);
+        matrix.set(15, 3);
+        matrix.set(5, 4);
+
+        for j in 0..16 {
+            let bits = matrix.column_bits(j);
+            for i in 0..16 {
+                assert_eq!(bits >> (63 - i) & 1 == 1, matrix.get(i, j));
+            }
+        }
+        assert_eq!(
+            matrix.column_bits(3),
+            (1 << 63) | (1 << (63 - 5)) | (1 << (63 - 15))
+        );
+        assert_eq!(matrix.column_bits(3), matrix.transpose().row_as_u64(3));
+    }
+
+    #[test]
+    fn test_bitstring() {
+        let mut matrix = BitMatrix::<1>::new();
+        matrix.set(0, 0);
+        matrix.set(2, 7);
+        matrix.set(7, 3);
+
+        let bitstring = matrix.to_bitstring();
+        assert_eq!(bitstring.len(), 64);
+        assert_eq!(&bitstring[..8], "10000000");
+        assert_eq!(&bitstring[16..24], "00000001");
+        assert_eq!(&bitstring[56..], "00010000");
+        assert_eq!(BitMatrix::<1>::from_bitstring(&bitstring), Some(matrix));
+
+        assert_eq!(BitMatrix::<1>::from_bitstring("101"), None);
+        assert_eq!(BitMatrix::<1>::from_bitstring(&"2".repeat(64)), None);
+    }
+}
@@ -1,3 +1,6 @@
+use num::traits::WrappingSub;
+use num::PrimInt;
+
 pub trait BitSet: Sized {
     /// The type used to index into the bit set.
     type Index;
@@ -17,9 +20,14 @@ pub trait BitSet: Sized {
     /// Returns the bit at `index`.
     fn get(&self, index: Self::Index) -> bool;
 
-    /// Returns the number of `true` bits in the set.
+    /// Returns the number of `true` bits in the set, i.e. its cardinality (not the highest index).
     fn len(&self) -> Self::Index;
 
+    /// Returns the number of `true` bits in the set. This is an alias for [`BitSet::len`].
+    fn cardinality(&self) -> Self::Index {
+        self.len()
+    }
+
     /// Returns `true` if the set contains no `true` bits.
     fn is_empty(&self) -> bool;
 
@@ -35,11 +43,50 @@ pub trait BitSet: Sized {
     /// Returns the union, i.e. the bits that are in `self` or `other`.
     fn union(&self, other: &Self) -> Self;
 
+    /// Removes the bits that are not in `other`, i.e. the in-place version of [`BitSet::intersection`].
+    fn keep_only(&mut self, other: &Self) {
+        *self = self.intersection(other);
+    }
+
+    /// Removes the bits that are in `other`, i.e. the in-place version of [`BitSet::difference`].
+    fn remove_all(&mut self, other: &Self) {
+        *self = self.difference(other);
+    }
+
     /// Returns `true` if `self` and `other` have no bits in common. This is equivalent to checking for an empty
     /// intersection.
     fn is_disjoint(&self, other: &Self) -> bool {
         self.intersection(other).is_empty()
     }
+
+    /// Returns the set containing exactly the given indices.
+    fn from_indices(indices: impl IntoIterator<Item = Self::Index>) -> Self;
+
+    /// Returns an iterator over the indices of the `true` bits, in increasing order.
+    fn to_indices(&self) -> impl Iterator<Item = Self::Index>;
+
+    /// Returns an iterator over the indices of the bits that are both in `self` and `other`, in increasing order.
+    fn iter_intersection(&self, other: &Self) -> impl Iterator<Item = Self::Index>;
+
+    /// Returns an iterator over the indices of the bits that are in `self` but not in `other`, in increasing order.
+    fn iter_difference(&self, other: &Self) -> impl Iterator<Item = Self::Index>;
+
+    /// Returns an iterator over the indices of the bits that are in `self` or `other` but not in both, in increasing
+    /// order.
+    fn iter_symmetric_difference(&self, other: &Self) -> impl Iterator<Item = Self::Index>;
+
+    /// Returns an iterator over every subset of `self`, starting with the empty set and ending with `self`.
+    fn subsets(&self) -> impl Iterator<Item = Self>;
+
+    /// Returns the set with the lowest `len` bits in reverse order, i.e. index `i` maps to `len - 1 - i`. Bits at index
+    /// `len` and above are dropped.
+    fn reverse_bits_within(&self, len: Self::Index) -> Self;
+
+    /// Returns the set with every index `i` moved to `(i + n) % BITS`, wrapping around the full width of the set.
+    fn rotate_indices_left(&self, n: u32) -> Self;
+
+    /// Returns the set with every index `i` moved to `(i - n) % BITS`, wrapping around the full width of the set.
+    fn rotate_indices_right(&self, n: u32) -> Self;
 }
 
 macro_rules! impl_bitset {
@@ -106,9 +153,162 @@ macro_rules! impl_bitset {
             fn is_disjoint(&self, other: &$t) -> bool {
                 self & other == 0
             }
+
+            #[inline]
+            fn from_indices(indices: impl IntoIterator<Item = $t>) -> $t {
+                indices.into_iter().fold(0, |set, index| set | 1 << index)
+            }
+
+            #[inline]
+            fn to_indices(&self) -> impl Iterator<Item = $t> {
+                iter_indices(*self)
+            }
+
+            #[inline]
+            fn iter_intersection(&self, other: &$t) -> impl Iterator<Item = $t> {
+                iter_indices(self & other)
+            }
+
+            #[inline]
+            fn iter_difference(&self, other: &$t) -> impl Iterator<Item = $t> {
+                iter_indices(self & !other)
+            }
+
+            #[inline]
+            fn iter_symmetric_difference(&self, other: &$t) -> impl Iterator<Item = $t> {
+                iter_indices(self ^ other)
+            }
+
+            #[inline]
+            fn subsets(&self) -> impl Iterator<Item = $t> {
+                let set = *self;
+                std::iter::successors(Some(0), move |&subset: &$t| {
+                    let next = subset.wrapping_sub(set) & set;
+                    (next != 0).then_some(next)
+                })
+            }
+
+            #[inline]
+            fn reverse_bits_within(&self, len: $t) -> $t {
+                let len = len as u32;
+                debug_assert!(len <= <$t>::BITS);
+                if len == 0 {
+                    return 0;
+                }
+                let mask = if len == <$t>::BITS { !0 } else { !(!0 << len) };
+                (self.reverse_bits() >> (<$t>::BITS - len)) & mask
+            }
+
+            #[inline]
+            fn rotate_indices_left(&self, n: u32) -> $t {
+                <$t>::rotate_left(*self, n)
+            }
+
+            #[inline]
+            fn rotate_indices_right(&self, n: u32) -> $t {
+                <$t>::rotate_right(*self, n)
+            }
         }
     )*)
 }
 
+/// Returns an iterator over the indices of the set bits in `bits`, in increasing order.
+#[inline]
+fn iter_indices<T: PrimInt + WrappingSub>(mut bits: T) -> impl Iterator<Item = T> {
+    std::iter::from_fn(move || {
+        if bits.is_zero() {
+            return None;
+        }
+        let index = T::from(bits.trailing_zeros()).unwrap();
+        bits = bits & bits.wrapping_sub(&T::one());
+        Some(index)
+    })
+}
+
 impl_bitset!(u8 u16 u32 u64 usize);
 impl_bitset!(i8 i16 i32 i64 isize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cardinality() {
+        assert_eq!(0b1011u32.cardinality(), 3);
+        assert_eq!(0u8.cardinality(), 0);
+    }
+
+    #[test]
+    fn test_subsets() {
+        let subsets = 0b10110u8.subsets().collect::<Vec<_>>();
+        assert_eq!(subsets.len(), 8);
+        assert_eq!(subsets.first(), Some(&0));
+        assert_eq!(subsets.last(), Some(&0b10110));
+        for subset in subsets {
+            assert_eq!(subset & !0b10110, 0);
+        }
+    }
+
+    #[test]
+    fn test_from_to_indices() {
+        let set = u32::from_indices([0, 2, 5]);
+        assert_eq!(set, 0b100101);
+        assert_eq!(set.to_indices().collect::<Vec<_>>(), [0, 2, 5]);
+
+        let set = i8::from_indices([0, 2, 7]);
+        assert_eq!(set.to_indices().collect::<Vec<_>>(), [0, 2, 7]);
+    }
+
+    #[test]
+    fn test_keep_only_remove_all() {
+        let (a, b) = (0b1101_0110u8, 0b0111_0011u8);
+
+        let mut set = a;
+        set.keep_only(&b);
+        assert_eq!(set, a.intersection(&b));
+        assert_eq!(set, 0b0101_0010);
+
+        let mut set = a;
+        set.remove_all(&b);
+        assert_eq!(set, a.difference(&b));
+        assert_eq!(set, 0b1000_0100);
+    }
+
+    #[test]
+    fn test_iter_set_operations() {
+        let (a, b) = (0b1101_0110_0000_0001u32, 0b0111_0011_0000_0001u32);
+
+        assert_eq!(
+            a.iter_intersection(&b).collect::<Vec<_>>(),
+            a.intersection(&b).to_indices().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            a.iter_difference(&b).collect::<Vec<_>>(),
+            a.difference(&b).to_indices().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            a.iter_symmetric_difference(&b).collect::<Vec<_>>(),
+            a.symmetric_difference(&b).to_indices().collect::<Vec<_>>()
+        );
+
+        assert_eq!(a.iter_intersection(&b).collect::<Vec<_>>(), [0, 9, 12, 14]);
+        assert_eq!(a.iter_difference(&b).collect::<Vec<_>>(), [10, 15]);
+        assert_eq!(
+            a.iter_symmetric_difference(&b).collect::<Vec<_>>(),
+            [8, 10, 13, 15]
+        );
+    }
+
+    #[test]
+    fn test_reverse_and_rotate() {
+        let set = 0b1110_0110u8;
+        assert_eq!(set.reverse_bits_within(5), 0b0_1100);
+        assert_eq!(set.reverse_bits_within(8), set.reverse_bits());
+        assert_eq!(set.reverse_bits_within(0), 0);
+        assert_eq!((-1i8).reverse_bits_within(3), 0b111);
+
+        assert_eq!(set.rotate_indices_left(2), 0b1001_1011);
+        assert_eq!(set.rotate_indices_left(2).rotate_indices_right(2), set);
+        assert_eq!(set.rotate_indices_left(2), set.rotate_left(2));
+    }
+}
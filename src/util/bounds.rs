@@ -0,0 +1,77 @@
+use crate::util::coord::Coord;
+
+/// An axis-aligned bounding box, including both `min` and `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bounds<T> {
+    pub min: Coord<T>,
+    pub max: Coord<T>,
+}
+
+impl<T: Copy + PartialOrd> Bounds<T> {
+    pub fn new(min: Coord<T>, max: Coord<T>) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns the smallest bounds containing all the given points, or `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = Coord<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::new(first, first), |mut bounds, point| {
+            if point.x < bounds.min.x {
+                bounds.min.x = point.x;
+            }
+            if point.y < bounds.min.y {
+                bounds.min.y = point.y;
+            }
+            if point.x > bounds.max.x {
+                bounds.max.x = point.x;
+            }
+            if point.y > bounds.max.y {
+                bounds.max.y = point.y;
+            }
+            bounds
+        }))
+    }
+
+    /// Returns `true` if the coordinate lies within the bounds, including on the boundary.
+    pub fn contains(&self, coord: &Coord<T>) -> bool {
+        self.min.x <= coord.x
+            && coord.x <= self.max.x
+            && self.min.y <= coord.y
+            && coord.y <= self.max.y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        let bounds = Bounds::new(Coord::new(-2, 3), Coord::new(5, 7));
+
+        assert!(bounds.contains(&Coord::new(-2, 3)));
+        assert!(bounds.contains(&Coord::new(5, 7)));
+        assert!(bounds.contains(&Coord::new(-2, 7)));
+        assert!(bounds.contains(&Coord::new(0, 5)));
+
+        assert!(!bounds.contains(&Coord::new(-3, 5)));
+        assert!(!bounds.contains(&Coord::new(6, 5)));
+        assert!(!bounds.contains(&Coord::new(0, 2)));
+        assert!(!bounds.contains(&Coord::new(0, 8)));
+
+        let bounds = Bounds::new(Coord::new(7.0, 7.0), Coord::new(27.0, 27.0));
+        assert!(bounds.contains(&Coord::new(14.333, 15.333)));
+        assert!(!bounds.contains(&Coord::new(6.999, 15.333)));
+    }
+
+    #[test]
+    fn test_from_points() {
+        let points = [Coord::new(3, -1), Coord::new(-4, 2), Coord::new(0, 5)];
+        let bounds = Bounds::from_points(points).unwrap();
+        assert_eq!(bounds, Bounds::new(Coord::new(-4, -1), Coord::new(3, 5)));
+        assert!(points.iter().all(|point| bounds.contains(point)));
+
+        assert_eq!(Bounds::<i32>::from_points([]), None);
+    }
+}
@@ -0,0 +1,29 @@
+/// Counts the copies of each item, where every item starts with one copy, and each copy of item `i` adds a copy of
+/// each of the next `wins[i]` items (ignoring items past the end).
+///
+/// This is the scratchcard rule from day 4.
+pub fn cascade_counts(wins: &[u32]) -> Vec<u64> {
+    let mut counts = vec![1; wins.len()];
+    for i in 0..wins.len() {
+        let end = (i + 1 + wins[i] as usize).min(wins.len());
+        for j in i + 1..end {
+            counts[j] += counts[i];
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cascade_counts() {
+        let counts = cascade_counts(&[4, 2, 2, 1, 0, 0]);
+        assert_eq!(counts, [1, 2, 4, 8, 14, 1]);
+        assert_eq!(counts.iter().sum::<u64>(), 30);
+
+        // Wins past the end are ignored
+        assert_eq!(cascade_counts(&[0, 5]), [1, 1]);
+    }
+}
@@ -1,5 +1,3 @@
-use itertools::Itertools;
-
 /// A rectangular grid of characters.
 ///
 /// This is a wrapper around a `&[u8]` that allows for indexing by coordinates.
@@ -14,23 +12,21 @@ impl<'a> CharGrid<'a> {
     /// Create a new `CharGrid` from an ASCII string slice.
     ///
     /// The string slice must be rectangular, i.e. all lines must have the same length. The last
-    /// line may or may not have a trailing newline. Supports both `\n` and `\r\n` line endings.
+    /// line may or may not have a trailing newline. Only `\n` line endings are supported, use
+    /// [`read_normalized`](crate::util::read_normalized) to convert `\r\n` line endings first.
+    ///
+    /// Panics if the data is not ASCII, contains `\r`, or is not rectangular.
     pub fn new(data: &'a str) -> Self {
         assert!(data.is_ascii());
+        assert!(
+            !data.contains('\r'),
+            "data must only contain \\n line endings"
+        );
 
         let data = data.as_bytes();
 
-        let (width, line_sep_char) = data
-            .iter()
-            .find_position(|&c| matches!(c, b'\n' | b'\r'))
-            .unwrap();
-
-        let width_with_nl = width
-            + match line_sep_char {
-                b'\n' => 1,
-                b'\r' => 2,
-                _ => unreachable!(),
-            };
+        let width = data.iter().position(|&c| c == b'\n').unwrap();
+        let width_with_nl = width + 1;
 
         // Note: we allow the last line to not have a newline, hence the ceiling division
         let height = (data.len() + width_with_nl - 1) / width_with_nl;
@@ -77,4 +73,46 @@ impl<'a> CharGrid<'a> {
     pub unsafe fn get_unchecked(&self, x: usize, y: usize) -> char {
         *self.data.get_unchecked(y * self.width_with_nl + x) as char
     }
+
+    /// Returns an iterator over the contiguous runs of digits in each row, as `(value, y, x_start, x_end)` where the
+    /// digits span `x_start..x_end`.
+    pub fn scan_numbers(&self) -> impl Iterator<Item = (u32, usize, usize, usize)> + '_ {
+        (0..self.height).flat_map(move |y| {
+            let row_start = y * self.width_with_nl;
+            let row = &self.data[row_start..row_start + self.width];
+
+            let mut x = 0;
+            std::iter::from_fn(move || {
+                let x_start = x + row[x..].iter().position(u8::is_ascii_digit)?;
+                let x_end = row[x_start..]
+                    .iter()
+                    .position(|c| !c.is_ascii_digit())
+                    .map_or(row.len(), |len| x_start + len);
+                x = x_end;
+
+                let value = row[x_start..x_end]
+                    .iter()
+                    .fold(0, |num, &c| num * 10 + (c - b'0') as u32);
+                Some((value, y, x_start, x_end))
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_numbers() {
+        let grid = CharGrid::new("467..*\n..*.35\n");
+        let numbers = grid.scan_numbers().collect::<Vec<_>>();
+        assert_eq!(numbers, [(467, 0, 0, 3), (35, 1, 4, 6)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "data must only contain \\n line endings")]
+    fn test_new_rejects_crlf() {
+        CharGrid::new("467..*\r\n..*.35\r\n");
+    }
 }
@@ -1,6 +1,5 @@
-use crate::util::Indexer;
-use num::traits::WrappingAdd;
-use std::marker::PhantomData;
+use crate::util::{Indexer, KeyFor};
+use num::traits::{WrappingAdd, WrappingMul};
 
 // TODO: Rename to North, East, South, West
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -11,12 +10,20 @@ pub enum Direction {
     Left,
 }
 
-pub struct Up;
-pub struct Right;
-pub struct Down;
-pub struct Left;
-
 impl Direction {
+    /// All four directions, in the order the solvers have always explored them.
+    pub const ALL: [Self; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Right,
+        Direction::Left,
+    ];
+
+    /// Returns an iterator over all four directions, in the same order as [`Direction::ALL`].
+    pub fn all() -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter()
+    }
+
     pub fn opposite(self) -> Self {
         match self {
             Direction::Up => Direction::Down,
@@ -32,6 +39,26 @@ impl Direction {
             Direction::Right | Direction::Left => [Direction::Up, Direction::Down],
         }
     }
+
+    /// Returns the direction rotated 90 degrees clockwise.
+    pub fn rotate_cw(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    /// Returns the direction rotated 90 degrees counter-clockwise.
+    pub fn rotate_ccw(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
@@ -65,6 +92,25 @@ macro_rules! impl_coord {
 
 impl_coord!(u16, u32, usize);
 
+impl Coord<i32> {
+    /// Rotates the coordinate 90 degrees clockwise around the origin, in screen coordinates (i.e. y pointing down).
+    pub fn rotate90_cw(&self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotates the coordinate 90 degrees counter-clockwise around the origin, in screen coordinates.
+    pub fn rotate90_ccw(&self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    /// Rotates the coordinate 90 degrees clockwise within a `width` x `height` grid, such that it ends up in the
+    /// rotated `height` x `width` grid.
+    pub fn rotate90_cw_in(&self, _width: i32, height: i32) -> Self {
+        Self::new(height - 1 - self.y, self.x)
+    }
+}
+
+#[derive(Clone, Copy)]
 pub struct CoordStepper<T> {
     dx: T,
     dy: T,
@@ -86,6 +132,30 @@ impl<T> CoordStepper<T> {
             y: coord.y.wrapping_add(&self.dy),
         }
     }
+
+    /// Returns a stepper that takes `n` steps of this stepper at once.
+    #[inline]
+    pub fn scale(self, n: T) -> Self
+    where
+        T: WrappingMul,
+    {
+        Self {
+            dx: self.dx.wrapping_mul(&n),
+            dy: self.dy.wrapping_mul(&n),
+        }
+    }
+
+    /// Returns a stepper that takes a step of this stepper followed by a step of `other`.
+    #[inline]
+    pub fn compose(self, other: Self) -> Self
+    where
+        T: WrappingAdd,
+    {
+        Self {
+            dx: self.dx.wrapping_add(&other.dx),
+            dy: self.dy.wrapping_add(&other.dy),
+        }
+    }
 }
 
 macro_rules! impl_coord_stepper {
@@ -132,12 +202,26 @@ macro_rules! impl_coord_indexer {
                     (coord.y * self.width + coord.x) as usize
                 }
             }
+
+            impl KeyFor<Coord<$t>> for CoordIndexer<$t> {
+                fn key_for(&self, index: usize) -> Coord<$t> {
+                    let width = self.width as usize;
+                    Coord::new((index % width) as $t, (index / width) as $t)
+                }
+            }
         )*
     };
 }
 
 impl_coord_indexer!(u16, u32, usize);
 
+impl<T: PartialOrd> CoordIndexer<T> {
+    /// Returns `true` if the coordinate lies within the grid.
+    pub fn contains(&self, coord: &Coord<T>) -> bool {
+        coord.x < self.width && coord.y < self.height
+    }
+}
+
 impl CoordIndexer {
     /// Returns the coordinate one step in the given direction from the given coordinate, if it is in bounds.
     pub fn step(&self, coord: Coord, direction: Direction) -> Option<Coord> {
@@ -152,75 +236,165 @@ impl CoordIndexer {
     }
 }
 
-#[derive(Copy, Clone)]
-pub struct FlippedCoordIndexer<D> {
-    indexer: CoordIndexer,
-    _direction: PhantomData<D>,
+/// Indexer for the coordinates within Manhattan distance `radius` of `(radius, radius)`, packed densely row by row
+/// into `2 * radius * radius + 2 * radius + 1` slots.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DiamondIndexer<T = usize> {
+    pub radius: T,
 }
 
-impl<D> FlippedCoordIndexer<D> {
-    pub fn new(indexer: CoordIndexer) -> Self {
-        Self {
-            indexer,
-            _direction: PhantomData,
-        }
+impl<T> DiamondIndexer<T> {
+    pub fn new(radius: T) -> Self {
+        Self { radius }
     }
+}
 
-    pub fn width(&self) -> usize {
-        self.indexer.width
+/// Returns the index of the first coordinate in row `y` of a diamond with the given radius.
+#[inline]
+fn diamond_row_start(radius: usize, y: usize) -> usize {
+    if y <= radius {
+        y * y
+    } else {
+        let j = y - radius - 1;
+        (radius + 1) * (radius + 1) + 2 * radius * j - j * j
     }
+}
 
-    pub fn height(&self) -> usize {
-        self.indexer.height
-    }
+macro_rules! impl_diamond_indexer {
+    ($($t:ty),*) => {
+        $(
+            impl Indexer<Coord<$t>> for DiamondIndexer<$t> {
+                #[inline]
+                fn len(&self) -> usize {
+                    let radius = self.radius as usize;
+                    2 * radius * radius + 2 * radius + 1
+                }
+
+                #[inline]
+                fn index_for(&self, coord: &Coord<$t>) -> usize {
+                    let radius = self.radius as usize;
+                    let (x, y) = (coord.x as usize, coord.y as usize);
+                    let dy = y.abs_diff(radius);
+                    debug_assert!(x.abs_diff(radius) + dy <= radius);
+                    diamond_row_start(radius, y) + x - dy
+                }
+            }
+
+            impl KeyFor<Coord<$t>> for DiamondIndexer<$t> {
+                fn key_for(&self, index: usize) -> Coord<$t> {
+                    let radius = self.radius as usize;
+                    let mut y = 0;
+                    while diamond_row_start(radius, y + 1) <= index {
+                        y += 1;
+                    }
+                    let x = y.abs_diff(radius) + index - diamond_row_start(radius, y);
+                    Coord::new(x as $t, y as $t)
+                }
+            }
+        )*
+    };
 }
 
-impl Indexer<Coord> for FlippedCoordIndexer<Up> {
-    fn len(&self) -> usize {
-        self.indexer.len()
-    }
+impl_diamond_indexer!(u16, u32, usize);
+
+/// One of the eight symmetries of a rectangular grid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    /// Rotate 90 degrees clockwise.
+    Rot90,
+    Rot180,
+    /// Rotate 270 degrees clockwise (i.e. 90 degrees counter-clockwise).
+    Rot270,
+    /// Mirror left-to-right.
+    FlipH,
+    /// Mirror top-to-bottom.
+    FlipV,
+    /// Mirror along the main diagonal, from the top-left to the bottom-right corner.
+    TransposeMain,
+    /// Mirror along the anti-diagonal, from the top-right to the bottom-left corner.
+    TransposeAnti,
+}
 
-    fn index_for(&self, coord: &Coord) -> usize {
-        self.indexer.index_for(coord)
+impl Transform {
+    pub const ALL: [Self; 8] = [
+        Transform::Identity,
+        Transform::Rot90,
+        Transform::Rot180,
+        Transform::Rot270,
+        Transform::FlipH,
+        Transform::FlipV,
+        Transform::TransposeMain,
+        Transform::TransposeAnti,
+    ];
+
+    /// Returns `true` if the transform swaps the width and height of the grid.
+    pub fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Transform::Rot90
+                | Transform::Rot270
+                | Transform::TransposeMain
+                | Transform::TransposeAnti
+        )
     }
 }
 
-impl Indexer<Coord> for FlippedCoordIndexer<Right> {
-    fn len(&self) -> usize {
-        self.indexer.len()
+/// Indexer for a transformed view of a `CoordIndexer`'s grid, mapping coordinates in the view to the index of the
+/// corresponding coordinate in the original grid.
+#[derive(Copy, Clone)]
+pub struct TransformedIndexer {
+    indexer: CoordIndexer,
+    transform: Transform,
+}
+
+impl TransformedIndexer {
+    pub fn new(indexer: CoordIndexer, transform: Transform) -> Self {
+        Self { indexer, transform }
     }
 
-    fn index_for(&self, coord: &Coord) -> usize {
-        let Coord { x, y } = *coord;
-        self.indexer.index_for(&Coord {
-            x: self.indexer.width - 1 - y,
-            y: x,
-        })
+    pub fn transform(&self) -> Transform {
+        self.transform
     }
-}
 
-impl Indexer<Coord> for FlippedCoordIndexer<Down> {
-    fn len(&self) -> usize {
-        self.indexer.len()
+    /// The width of the transformed view.
+    pub fn width(&self) -> usize {
+        if self.transform.swaps_axes() {
+            self.indexer.height
+        } else {
+            self.indexer.width
+        }
     }
 
-    fn index_for(&self, coord: &Coord) -> usize {
-        let Coord { x, y } = *coord;
-        self.indexer.index_for(&Coord {
-            x,
-            y: self.indexer.height - 1 - y,
-        })
+    /// The height of the transformed view.
+    pub fn height(&self) -> usize {
+        if self.transform.swaps_axes() {
+            self.indexer.width
+        } else {
+            self.indexer.height
+        }
     }
 }
 
-impl Indexer<Coord> for FlippedCoordIndexer<Left> {
+impl Indexer<Coord> for TransformedIndexer {
     fn len(&self) -> usize {
         self.indexer.len()
     }
 
     fn index_for(&self, coord: &Coord) -> usize {
         let Coord { x, y } = *coord;
-        self.indexer.index_for(&Coord { x: y, y: x })
+        let CoordIndexer { width, height } = self.indexer;
+        let (x, y) = match self.transform {
+            Transform::Identity => (x, y),
+            Transform::Rot90 => (y, height - 1 - x),
+            Transform::Rot180 => (width - 1 - x, height - 1 - y),
+            Transform::Rot270 => (width - 1 - y, x),
+            Transform::FlipH => (width - 1 - x, y),
+            Transform::FlipV => (x, height - 1 - y),
+            Transform::TransposeMain => (y, x),
+            Transform::TransposeAnti => (width - 1 - y, height - 1 - x),
+        };
+        self.indexer.index_for(&Coord { x, y })
     }
 }
 
@@ -239,6 +413,24 @@ impl<T> DirectedCoord<T> {
     }
 }
 
+impl DirectedCoord {
+    /// Rotates the directed coordinate 90 degrees clockwise within a `width` x `height` grid.
+    ///
+    /// The rotated grid is `height` x `width`.
+    pub fn rotate_cw(&self, _width: usize, height: usize) -> Self {
+        let Coord { x, y } = self.coord;
+        Self::new(height - 1 - y, x, self.direction.rotate_cw())
+    }
+
+    /// Rotates the directed coordinate 90 degrees counter-clockwise within a `width` x `height` grid.
+    ///
+    /// The rotated grid is `height` x `width`.
+    pub fn rotate_ccw(&self, width: usize, _height: usize) -> Self {
+        let Coord { x, y } = self.coord;
+        Self::new(y, width - 1 - x, self.direction.rotate_ccw())
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct DirectedCoordIndexer<T = usize> {
     pub width: T,
@@ -299,3 +491,177 @@ impl Indexer<DirectedCoord<u32>> for DirectedCoordIndexer<u32> {
         ((y * self.width + x) * 4 + direction_index) as usize
     }
 }
+
+impl Indexer<DirectedCoord<u16>> for DirectedCoordIndexer<u16> {
+    fn len(&self) -> usize {
+        // Computed as usize, since the number of states may not fit in a u16
+        self.width as usize * self.height as usize * 4
+    }
+
+    fn index_for(&self, directed_coord: &DirectedCoord<u16>) -> usize {
+        let DirectedCoord {
+            coord: Coord { x, y },
+            direction,
+        } = *directed_coord;
+        let direction_index = match direction {
+            Direction::Up => 0,
+            Direction::Right => 1,
+            Direction::Down => 2,
+            Direction::Left => 3,
+        };
+        (y as usize * self.width as usize + x as usize) * 4 + direction_index
+    }
+}
+
+impl KeyFor<DirectedCoord<u16>> for DirectedCoordIndexer<u16> {
+    fn key_for(&self, index: usize) -> DirectedCoord<u16> {
+        let direction = match index % 4 {
+            0 => Direction::Up,
+            1 => Direction::Right,
+            2 => Direction::Down,
+            _ => Direction::Left,
+        };
+        let coord_index = index / 4;
+        let x = (coord_index % self.width as usize) as u16;
+        let y = (coord_index / self.width as usize) as u16;
+        DirectedCoord::new(x, y, direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directed_coord_indexer_u16() {
+        let indexer = DirectedCoordIndexer::<u16>::new(3, 2);
+        assert_eq!(indexer.len(), 24);
+
+        let mut index = 0;
+        for y in 0..2 {
+            for x in 0..3 {
+                for direction in [
+                    Direction::Up,
+                    Direction::Right,
+                    Direction::Down,
+                    Direction::Left,
+                ] {
+                    let directed_coord = DirectedCoord::new(x, y, direction);
+                    assert_eq!(indexer.index_for(&directed_coord), index);
+                    assert_eq!(indexer.key_for(index), directed_coord);
+                    index += 1;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_diamond_indexer() {
+        for radius in 0..6usize {
+            let indexer = DiamondIndexer::new(radius);
+            assert_eq!(indexer.len(), 2 * radius * radius + 2 * radius + 1);
+
+            let coords = (0..=2 * radius)
+                .flat_map(|y| (0..=2 * radius).map(move |x| Coord::new(x, y)))
+                .filter(|coord| coord.x.abs_diff(radius) + coord.y.abs_diff(radius) <= radius)
+                .collect::<Vec<_>>();
+            assert_eq!(coords.len(), indexer.len());
+            for (index, coord) in coords.iter().enumerate() {
+                assert_eq!(indexer.index_for(coord), index);
+                assert_eq!(indexer.key_for(index), *coord);
+            }
+        }
+    }
+
+    #[test]
+    fn test_direction_all() {
+        let directions = Direction::all().collect::<Vec<_>>();
+        assert_eq!(directions.len(), 4);
+        for direction in [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ] {
+            assert!(directions.contains(&direction));
+        }
+    }
+
+    #[test]
+    fn test_coord_stepper_scale_compose() {
+        let right = CoordStepper::<usize>::from_direction(Direction::Right);
+        assert_eq!(right.scale(5).step(Coord::new(0, 0)), Coord::new(5, 0));
+
+        let left = CoordStepper::<usize>::from_direction(Direction::Left);
+        assert_eq!(left.scale(3).step(Coord::new(10, 2)), Coord::new(7, 2));
+
+        let down = CoordStepper::<usize>::from_direction(Direction::Down);
+        let diagonal = left.scale(2).compose(down.scale(4));
+        assert_eq!(diagonal.step(Coord::new(10, 2)), Coord::new(8, 6));
+    }
+
+    #[test]
+    fn test_coord_rotate90() {
+        assert_eq!(Coord::new(1, 0).rotate90_cw(), Coord::new(0, 1));
+        assert_eq!(Coord::new(0, 1).rotate90_ccw(), Coord::new(1, 0));
+
+        let coord = Coord::new(3, -2);
+        let rotated = (0..4).fold(coord, |coord, _| coord.rotate90_cw());
+        assert_eq!(rotated, coord);
+
+        // Top-right corner of a 3x2 grid becomes the bottom-right corner of a 2x3 grid
+        assert_eq!(Coord::new(2, 0).rotate90_cw_in(3, 2), Coord::new(1, 2));
+    }
+
+    #[test]
+    fn test_directed_coord_rotate() {
+        // Top-right corner of a 3x2 grid, facing right
+        let directed_coord = DirectedCoord::new(2, 0, Direction::Right);
+
+        // Becomes the bottom-right corner of a 2x3 grid, facing down
+        let rotated = directed_coord.rotate_cw(3, 2);
+        assert_eq!(rotated, DirectedCoord::new(1, 2, Direction::Down));
+
+        assert_eq!(rotated.rotate_ccw(2, 3), directed_coord);
+    }
+
+    #[test]
+    fn test_transformed_indexer() {
+        // Indices in the original 3x2 grid:
+        //   0 1 2
+        //   3 4 5
+        let cases = [
+            (Transform::Identity, vec![0, 1, 2, 3, 4, 5]),
+            (Transform::Rot90, vec![3, 0, 4, 1, 5, 2]),
+            (Transform::Rot180, vec![5, 4, 3, 2, 1, 0]),
+            (Transform::Rot270, vec![2, 5, 1, 4, 0, 3]),
+            (Transform::FlipH, vec![2, 1, 0, 5, 4, 3]),
+            (Transform::FlipV, vec![3, 4, 5, 0, 1, 2]),
+            (Transform::TransposeMain, vec![0, 3, 1, 4, 2, 5]),
+            (Transform::TransposeAnti, vec![5, 2, 4, 1, 3, 0]),
+        ];
+        assert_eq!(cases.len(), Transform::ALL.len());
+
+        for (transform, expected) in cases {
+            let indexer = TransformedIndexer::new(CoordIndexer::new(3, 2), transform);
+            assert_eq!(indexer.len(), 6);
+
+            let indices = (0..indexer.height())
+                .flat_map(|y| (0..indexer.width()).map(move |x| Coord::new(x, y)))
+                .map(|coord| indexer.index_for(&coord))
+                .collect::<Vec<_>>();
+            assert_eq!(indices, expected, "{:?}", transform);
+        }
+    }
+
+    #[test]
+    fn test_coord_indexer_contains() {
+        let indexer = CoordIndexer::<u32>::new(3, 2);
+        assert!(indexer.contains(&Coord::new(0, 0)));
+        assert!(indexer.contains(&Coord::new(1, 1)));
+        assert!(indexer.contains(&Coord::new(2, 1)));
+        assert!(!indexer.contains(&Coord::new(3, 1)));
+        assert!(!indexer.contains(&Coord::new(2, 2)));
+        assert!(!indexer.contains(&Coord::new(100, 0)));
+    }
+}
@@ -0,0 +1,41 @@
+/// Finds the first and last digit in the line, where besides the ASCII digits, each `(word, digit)` pair in `words` is
+/// also recognized as a digit. Matches may overlap, e.g. `"twone"` has first digit 2 and last digit 1.
+///
+/// Returns `None` if the line has no digits.
+pub fn first_last_digit(line: &str, words: &[(&str, u32)]) -> Option<(u32, u32)> {
+    let first = (0..line.len()).find_map(|index| {
+        let s = line.get(index..)?;
+        s.chars().next()?.to_digit(10).or_else(|| {
+            words
+                .iter()
+                .find(|(word, _)| s.starts_with(word))
+                .map(|&(_, digit)| digit)
+        })
+    })?;
+    let last = (0..line.len()).rev().find_map(|index| {
+        let s = line.get(..=index)?;
+        s.chars().next_back()?.to_digit(10).or_else(|| {
+            words
+                .iter()
+                .find(|(word, _)| s.ends_with(word))
+                .map(|&(_, digit)| digit)
+        })
+    })?;
+    Some((first, last))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_last_digit() {
+        assert_eq!(first_last_digit("pqr3stu8vwx", &[]), Some((3, 8)));
+        assert_eq!(first_last_digit("treb7uchet", &[]), Some((7, 7)));
+        assert_eq!(first_last_digit("twone", &[]), None);
+
+        let words = [("one", 1), ("two", 2)];
+        assert_eq!(first_last_digit("twone", &words), Some((2, 1)));
+        assert_eq!(first_last_digit("x4ü two", &words), Some((4, 2)));
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt::Display;
+use std::io::Write;
+use std::process::Command;
+
+use petgraph::dot::{Config, Dot};
+use petgraph::graph::{DiGraph, NodeIndex as PetNodeIndex};
+
+use crate::util::{Indexer, LinearIndexer, VecTable};
+
+pub type NodeIndex = u32;
+
+/// Adjacency list of a directed graph with nodes `0..len()` and edge costs of type `C`.
+#[derive(Debug, Clone)]
+pub struct AdjList<C> {
+    table: VecTable<NodeIndex, Vec<(NodeIndex, C)>, LinearIndexer<NodeIndex>>,
+}
+
+impl<C> AdjList<C> {
+    /// Creates an adjacency list with `len` nodes and no edges.
+    pub fn new(len: NodeIndex) -> Self {
+        Self {
+            table: VecTable::new(LinearIndexer::new(len)),
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn len(&self) -> NodeIndex {
+        self.table.indexer().len() as NodeIndex
+    }
+
+    /// Returns `true` if the graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a directed edge from `from` to `to` with the given cost.
+    pub fn add_edge(&mut self, from: NodeIndex, to: NodeIndex, cost: C) {
+        self.table[from].push((to, cost));
+    }
+
+    /// Temporarily removes the edge from `from` to `to` while running `f`, and re-inserts it at its original position
+    /// afterwards, even if `f` panics. Nested calls restore the graph in reverse order, so the graph is left exactly as
+    /// it was found.
+    ///
+    /// Panics if there is no edge from `from` to `to`.
+    pub fn with_edge_removed<R>(
+        &mut self,
+        from: NodeIndex,
+        to: NodeIndex,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let position = self.table[from]
+            .iter()
+            .position(|&(target, _)| target == to)
+            .expect("edge should exist");
+        let edge = self.table[from].remove(position);
+
+        let guard = RemovedEdge {
+            adj: self,
+            from,
+            position,
+            edge: Some(edge),
+        };
+        f(guard.adj)
+    }
+
+    /// Returns the outgoing edges of `node` as `(target, cost)` pairs.
+    pub fn neighbors(&self, node: NodeIndex) -> &[(NodeIndex, C)] {
+        &self.table[node]
+    }
+
+    /// Returns an iterator over all edges as `(source, target, cost)` triples.
+    pub fn edges(&self) -> impl Iterator<Item = (NodeIndex, NodeIndex, &C)> {
+        self.table.iter().flat_map(|(source, neighbors)| {
+            neighbors
+                .iter()
+                .map(move |(target, cost)| (source, *target, cost))
+        })
+    }
+}
+
+/// Re-inserts an edge removed by [`AdjList::with_edge_removed`] when dropped, so it's also restored while unwinding.
+struct RemovedEdge<'a, C> {
+    adj: &'a mut AdjList<C>,
+    from: NodeIndex,
+    position: usize,
+    edge: Option<(NodeIndex, C)>,
+}
+
+impl<C> Drop for RemovedEdge<'_, C> {
+    fn drop(&mut self) {
+        if let Some(edge) = self.edge.take() {
+            self.adj.table[self.from].insert(self.position, edge);
+        }
+    }
+}
+
+/// Converts an adjacency list to a `petgraph` graph, preserving node indices and edge costs.
+pub fn from_adjacency<C: Clone>(adj: &AdjList<C>) -> DiGraph<(), C> {
+    let mut graph = DiGraph::with_capacity(adj.len() as usize, 0);
+    for _ in 0..adj.len() {
+        graph.add_node(());
+    }
+    for (source, target, cost) in adj.edges() {
+        graph.add_edge(
+            PetNodeIndex::new(source as usize),
+            PetNodeIndex::new(target as usize),
+            cost.clone(),
+        );
+    }
+    graph
+}
+
+/// Writes `graph` to `data/viz/{name}.dot` and renders it to `data/viz/{name}.svg` with the given Graphviz layout
+/// `engine` (e.g. `dot` or `neato`). This is meant for debug output, so if Graphviz isn't installed (or fails), it
+/// prints a warning instead of panicking.
+pub fn print_graph<E: Display>(
+    graph: &DiGraph<String, E>,
+    name: &str,
+    engine: &str,
+    config: &[Config],
+) {
+    let dot_path = format!("data/viz/{name}.dot");
+    let svg_path = format!("data/viz/{name}.svg");
+
+    let mut file = std::fs::File::create(&dot_path).unwrap();
+    write!(file, "{}", Dot::with_config(graph, config)).unwrap();
+
+    // Run dot to generate SVG
+    let output = Command::new("dot")
+        .args(["-Tsvg", &dot_path, "-o", &svg_path, &format!("-K{engine}")])
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => eprintln!(
+            "Warning: dot failed to render {dot_path}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(err) => eprintln!("Warning: could not run dot to render {dot_path}: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_adjacency() {
+        let mut adj = AdjList::new(4);
+        adj.add_edge(0, 1, 5);
+        adj.add_edge(0, 2, 3);
+        adj.add_edge(2, 3, 7);
+        adj.add_edge(3, 0, 1);
+
+        let graph = from_adjacency(&adj);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+
+        for (source, target, cost) in adj.edges() {
+            let edge = graph
+                .find_edge(
+                    PetNodeIndex::new(source as usize),
+                    PetNodeIndex::new(target as usize),
+                )
+                .unwrap();
+            assert_eq!(graph.edge_weight(edge), Some(cost));
+        }
+    }
+
+    #[test]
+    fn test_with_edge_removed() {
+        let mut adj = AdjList::new(3);
+        adj.add_edge(0, 1, 'a');
+        adj.add_edge(0, 2, 'b');
+        adj.add_edge(0, 1, 'c');
+        adj.add_edge(1, 2, 'd');
+
+        let before = adj
+            .clone()
+            .edges()
+            .map(|(s, t, &c)| (s, t, c))
+            .collect::<Vec<_>>();
+
+        let neighbors = adj.with_edge_removed(0, 1, |adj| {
+            adj.with_edge_removed(1, 2, |adj| assert!(adj.neighbors(1).is_empty()));
+            adj.neighbors(0).to_vec()
+        });
+        assert_eq!(neighbors, [(2, 'b'), (1, 'c')]);
+
+        let after = adj.edges().map(|(s, t, &c)| (s, t, c)).collect::<Vec<_>>();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_with_edge_removed_panic() {
+        let mut adj = AdjList::new(2);
+        adj.add_edge(0, 1, 'a');
+        adj.add_edge(0, 0, 'b');
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            adj.with_edge_removed(0, 1, |_| panic!("search failed"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(adj.neighbors(0), [(1, 'a'), (0, 'b')]);
+    }
+}
@@ -0,0 +1,97 @@
+use num::PrimInt;
+
+use crate::util::coord::{Coord, CoordIndexer, Direction};
+use crate::util::shortest_path::Problem;
+use crate::util::VecTable;
+
+/// Shortest path problem on a 4-connected grid, where entering a cell costs the value stored for that cell.
+pub struct GridProblem<C = u32> {
+    costs: VecTable<Coord, C, CoordIndexer>,
+    source: Coord,
+    target: Coord,
+    /// The cheapest cell cost, used to scale the Manhattan distance heuristic so it stays admissible.
+    min_cost: C,
+}
+
+impl<C: PrimInt> GridProblem<C> {
+    pub fn new(costs: VecTable<Coord, C, CoordIndexer>, source: Coord, target: Coord) -> Self {
+        let min_cost = costs.values().copied().min().unwrap_or_else(C::zero);
+        Self {
+            costs,
+            source,
+            target,
+            min_cost,
+        }
+    }
+}
+
+impl<C: PrimInt> Problem for GridProblem<C> {
+    type State = Coord;
+    type Cost = C;
+
+    fn sources(&self) -> impl IntoIterator<Item = Self::State> {
+        [self.source]
+    }
+
+    fn is_target(&self, state: &Self::State) -> bool {
+        *state == self.target
+    }
+
+    fn successors(
+        &self,
+        state: &Self::State,
+    ) -> impl IntoIterator<Item = (Self::State, Self::Cost)> {
+        let coord = *state;
+        Direction::all().filter_map(move |direction| {
+            let next_coord = self.costs.indexer().step(coord, direction)?;
+            Some((next_coord, *self.costs.get(&next_coord)))
+        })
+    }
+
+    fn heuristic(&self, state: &Self::State) -> Self::Cost {
+        // Manhattan distance to the target, where each step costs at least `min_cost`
+        let distance = state.x.abs_diff(self.target.x) + state.y.abs_diff(self.target.y);
+        if self.min_cost.is_zero() {
+            return C::zero();
+        }
+
+        // If the estimate doesn't fit in `C`, neither does the cost of any path to the target, so saturating keeps it
+        // admissible
+        C::from(distance)
+            .and_then(|distance| distance.checked_mul(&self.min_cost))
+            .unwrap_or_else(C::max_value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::shortest_path::a_star;
+    use crate::util::test::{grid_from_rows, HashCostMap, HeapOpenSet};
+
+    #[test]
+    fn test_grid_problem() {
+        // The cheapest path detours through the column of ones on the right
+        let costs = VecTable::from_grid_str(&grid_from_rows(&["1991", "1991", "9111"]), |c| {
+            c.to_digit(10).unwrap()
+        })
+        .unwrap();
+        let problem = GridProblem::new(costs, Coord::new(0, 0), Coord::new(3, 0));
+
+        let result = a_star(problem, HeapOpenSet::new(), HashCostMap::new());
+        assert_eq!(result, Some(1 + 9 + 1 + 1 + 1 + 1 + 1));
+    }
+
+    #[test]
+    fn test_heuristic_saturates() {
+        // A single row of 300 cells, so the distance from one end to the other doesn't fit in a `u8`
+        let row = "2".repeat(300);
+        let costs =
+            VecTable::from_grid_str(&grid_from_rows(&[&row]), |c| c.to_digit(10).unwrap() as u8)
+                .unwrap();
+        let problem = GridProblem::new(costs, Coord::new(0, 0), Coord::new(299, 0));
+        assert_eq!(problem.heuristic(&Coord::new(0, 0)), u8::MAX);
+        assert_eq!(problem.heuristic(&Coord::new(200, 0)), 99 * 2);
+        assert_eq!(problem.heuristic(&Coord::new(100, 0)), u8::MAX);
+    }
+}
@@ -1,3 +1,5 @@
+use std::marker::PhantomData;
+
 #[allow(clippy::len_without_is_empty)]
 pub trait Indexer<K> {
     /// Returns the required length of the data vector.
@@ -6,6 +8,15 @@ pub trait Indexer<K> {
     /// Returns the index of the key in the data vector.
     /// This index is guaranteed to be in bounds (i.e. less than `len()`).
     fn index_for(&self, key: &K) -> usize;
+
+    /// Adapts this indexer to keys of type `K2`, by mapping them to keys of type `K` with `f`.
+    fn map_key<K2, F>(self, f: F) -> MappedIndexer<Self, F, K>
+    where
+        Self: Sized,
+        F: Fn(&K2) -> K,
+    {
+        MappedIndexer::new(self, f)
+    }
 }
 
 pub trait KeyFor<K>: Indexer<K> {
@@ -57,3 +68,250 @@ macro_rules! impl_linear_indexer {
 }
 
 impl_linear_indexer!(u8, u16, u32, u64, u128, usize);
+
+/// Indexer for unordered pairs `(i, j)` with `i < j < n`, packed densely into `n * (n - 1) / 2` slots.
+#[derive(Debug, Clone, Copy)]
+pub struct TriangularIndexer {
+    n: usize,
+}
+
+impl TriangularIndexer {
+    pub fn new(n: usize) -> Self {
+        Self { n }
+    }
+
+    /// Returns the index of the first pair `(i, i + 1)` in row `i`.
+    #[inline]
+    fn row_start(&self, i: usize) -> usize {
+        i * (2 * self.n - i - 1) / 2
+    }
+}
+
+impl Indexer<(usize, usize)> for TriangularIndexer {
+    #[inline]
+    fn len(&self) -> usize {
+        self.n * self.n.saturating_sub(1) / 2
+    }
+
+    #[inline]
+    fn index_for(&self, &(i, j): &(usize, usize)) -> usize {
+        debug_assert!(i < j && j < self.n);
+        self.row_start(i) + (j - i - 1)
+    }
+}
+
+impl KeyFor<(usize, usize)> for TriangularIndexer {
+    fn key_for(&self, index: usize) -> (usize, usize) {
+        let mut i = 0;
+        while self.row_start(i + 1) <= index {
+            i += 1;
+        }
+        (i, i + 1 + index - self.row_start(i))
+    }
+}
+
+/// Indexer for pairs `(key, discriminant)` with `discriminant < 2^BITS`, packed as `(index << BITS) | discriminant`,
+/// where `index` is the index of `key` in the wrapped indexer.
+#[derive(Debug, Clone, Copy)]
+pub struct BitPackIndexer<I, const BITS: usize> {
+    indexer: I,
+}
+
+impl<I, const BITS: usize> BitPackIndexer<I, BITS> {
+    pub fn new(indexer: I) -> Self {
+        Self { indexer }
+    }
+}
+
+impl<I, K, const BITS: usize> Indexer<(K, usize)> for BitPackIndexer<I, BITS>
+where
+    I: Indexer<K>,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.indexer.len() << BITS
+    }
+
+    #[inline]
+    fn index_for(&self, (key, discriminant): &(K, usize)) -> usize {
+        debug_assert!(*discriminant < 1 << BITS);
+        (self.indexer.index_for(key) << BITS) | discriminant
+    }
+}
+
+impl<I, K, const BITS: usize> KeyFor<(K, usize)> for BitPackIndexer<I, BITS>
+where
+    I: KeyFor<K>,
+{
+    #[inline]
+    fn key_for(&self, index: usize) -> (K, usize) {
+        (
+            self.indexer.key_for(index >> BITS),
+            index & ((1 << BITS) - 1),
+        )
+    }
+}
+
+/// Indexer for keys of some type `K2`, which are mapped to keys of type `K` for the wrapped indexer.
+#[derive(Debug, Clone, Copy)]
+pub struct MappedIndexer<I, F, K> {
+    indexer: I,
+    f: F,
+    _key: PhantomData<fn() -> K>,
+}
+
+impl<I, F, K> MappedIndexer<I, F, K> {
+    pub fn new(indexer: I, f: F) -> Self {
+        Self {
+            indexer,
+            f,
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<I, F, K, K2> Indexer<K2> for MappedIndexer<I, F, K>
+where
+    I: Indexer<K>,
+    F: Fn(&K2) -> K,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.indexer.len()
+    }
+
+    #[inline]
+    fn index_for(&self, key: &K2) -> usize {
+        self.indexer.index_for(&(self.f)(key))
+    }
+}
+
+/// Indexer that only supports the keys of the wrapped indexer that are not masked out, packed densely into the range
+/// `0..count`, where `count` is the number of unmasked keys.
+#[derive(Debug, Clone)]
+pub struct MaskedIndexer<I> {
+    indexer: I,
+    /// Maps indices of the wrapped indexer to dense indices, with `usize::MAX` for masked out keys.
+    dense_indices: Box<[usize]>,
+    /// Maps dense indices to indices of the wrapped indexer.
+    inner_indices: Box<[usize]>,
+}
+
+impl<I> MaskedIndexer<I> {
+    /// Creates a new `MaskedIndexer`, where `mask[i]` is `true` if the key at index `i` of `indexer` is supported.
+    pub fn new(indexer: I, mask: &[bool]) -> Self {
+        let inner_indices = (0..mask.len()).filter(|&i| mask[i]).collect::<Box<[_]>>();
+        let mut dense_indices = vec![usize::MAX; mask.len()].into_boxed_slice();
+        for (dense_index, &inner_index) in inner_indices.iter().enumerate() {
+            dense_indices[inner_index] = dense_index;
+        }
+
+        Self {
+            indexer,
+            dense_indices,
+            inner_indices,
+        }
+    }
+
+    /// Returns `true` if the key is not masked out.
+    pub fn contains<K>(&self, key: &K) -> bool
+    where
+        I: Indexer<K>,
+    {
+        self.dense_indices[self.indexer.index_for(key)] != usize::MAX
+    }
+}
+
+impl<I, K> Indexer<K> for MaskedIndexer<I>
+where
+    I: Indexer<K>,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.inner_indices.len()
+    }
+
+    #[inline]
+    fn index_for(&self, key: &K) -> usize {
+        let index = self.dense_indices[self.indexer.index_for(key)];
+        debug_assert_ne!(index, usize::MAX, "key is masked out");
+        index
+    }
+}
+
+impl<I, K> KeyFor<K> for MaskedIndexer<I>
+where
+    I: KeyFor<K>,
+{
+    #[inline]
+    fn key_for(&self, index: usize) -> K {
+        self.indexer.key_for(self.inner_indices[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_triangular_indexer_round_trip() {
+        let indexer = TriangularIndexer::new(5);
+        assert_eq!(indexer.len(), 10);
+
+        let pairs = (0..5).tuple_combinations().collect_vec();
+        for (index, pair) in pairs.iter().enumerate() {
+            assert_eq!(indexer.index_for(pair), index);
+            assert_eq!(indexer.key_for(index), *pair);
+        }
+        assert_eq!(indexer.iter().collect_vec(), pairs);
+    }
+
+    #[test]
+    fn test_mapped_indexer() {
+        let indexer = LinearIndexer::new(26usize).map_key(|c: &char| (*c as u8 - b'a') as usize);
+        assert_eq!(Indexer::<char>::len(&indexer), 26);
+        assert_eq!(indexer.index_for(&'a'), 0);
+        assert_eq!(indexer.index_for(&'z'), 25);
+    }
+
+    #[test]
+    fn test_masked_indexer() {
+        use crate::util::coord::{Coord, CoordIndexer};
+
+        // Checkerboard pattern, so half of the cells are masked out
+        let inner = CoordIndexer::new(4usize, 4);
+        let mask = inner
+            .iter()
+            .map(|c: Coord| (c.x + c.y) % 2 == 0)
+            .collect_vec();
+        let indexer = MaskedIndexer::new(inner, &mask);
+        assert_eq!(Indexer::<Coord>::len(&indexer), 8);
+
+        let keys = indexer.iter().collect_vec();
+        assert_eq!(keys.len(), 8);
+        for (index, key) in keys.iter().enumerate() {
+            assert!(indexer.contains(key));
+            assert_eq!(indexer.index_for(key), index);
+            assert_eq!(indexer.key_for(index), *key);
+        }
+        assert!(!indexer.contains(&Coord::new(1, 0)));
+    }
+
+    #[test]
+    fn test_bit_pack_indexer_round_trip() {
+        let indexer = BitPackIndexer::<_, 1>::new(LinearIndexer::new(5usize));
+        assert_eq!(indexer.len(), 10);
+        assert_eq!(indexer.index_for(&(3, 1)), (3 << 1) + 1);
+
+        let pairs = (0..5).cartesian_product(0..2).collect_vec();
+        for pair in &pairs {
+            assert_eq!(indexer.key_for(indexer.index_for(pair)), *pair);
+        }
+        assert_eq!(indexer.iter().collect_vec(), pairs);
+
+        let indexer = BitPackIndexer::<_, 2>::new(LinearIndexer::new(3u32));
+        assert_eq!(indexer.len(), 12);
+        assert_eq!(indexer.key_for(indexer.index_for(&(2, 3))), (2, 3));
+    }
+}
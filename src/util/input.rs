@@ -0,0 +1,26 @@
+use std::borrow::Cow;
+
+/// Normalizes line endings by stripping all `\r` characters, so parsers can assume `\n`-only input.
+///
+/// Only allocates if the input actually contains a `\r`.
+pub fn read_normalized(input: &str) -> Cow<'_, str> {
+    if input.contains('\r') {
+        Cow::Owned(input.replace('\r', ""))
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_normalized() {
+        let normalized = read_normalized("ab\r\ncd\r\n");
+        assert_eq!(normalized, "ab\ncd\n");
+        assert!(!normalized.contains('\r'));
+
+        assert!(matches!(read_normalized("ab\ncd"), Cow::Borrowed("ab\ncd")));
+    }
+}
@@ -0,0 +1,89 @@
+use num::Float;
+
+/// Solves the system of linear equations given by the augmented matrix `matrix`, using Gaussian elimination with
+/// partial pivoting. Each of the `N` rows contains the `N` coefficients followed by the constant, so `M` must be
+/// `N + 1`.
+///
+/// Returns `None` if the system is singular, i.e. if the best pivot in some column is (close to) zero.
+pub fn gaussian_elimination<F: Float, const N: usize, const M: usize>(
+    mut matrix: [[F; M]; N],
+) -> Option<[F; N]> {
+    // TODO: Integer version of this algorithm
+    debug_assert_eq!(M, N + 1);
+
+    // Pivots smaller than this are considered zero, relative to the magnitude of the coefficients in their column
+    let tolerances: [F; N] = std::array::from_fn(|i| {
+        matrix
+            .iter()
+            .fold(F::zero(), |max, row| max.max(row[i].abs()))
+            * F::epsilon()
+            * F::from(N).unwrap()
+    });
+
+    for i in 0..N {
+        // Find pivot for column i
+        let mut pivot_row = i;
+        for j in i + 1..N {
+            if matrix[j][i].abs() > matrix[pivot_row][i].abs() {
+                pivot_row = j;
+            }
+        }
+
+        if matrix[pivot_row][i].abs() <= tolerances[i] {
+            return None;
+        }
+
+        // Swap rows i and pivot_row
+        matrix.swap(i, pivot_row);
+
+        // Eliminate column i for rows i+1..N
+        let pivot = matrix[i];
+        for row in &mut matrix[i + 1..] {
+            let factor = row[i] / pivot[i];
+            for (value, &pivot_value) in row.iter_mut().zip(&pivot).skip(i) {
+                *value = *value - factor * pivot_value;
+            }
+        }
+    }
+
+    // Back substitution
+    let mut x = [F::zero(); N];
+    for i in (0..N).rev() {
+        x[i] = matrix[i][N];
+        for j in i + 1..N {
+            x[i] = x[i] - matrix[i][j] * x[j];
+        }
+        x[i] = x[i] / matrix[i][i];
+    }
+
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gaussian_elimination() {
+        // 2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3
+        let matrix = [[2., 1., -1., 8.], [-3., -1., 2., -11.], [-2., 1., 2., -3.]];
+
+        let [x, y, z] = gaussian_elimination::<f64, 3, 4>(matrix).unwrap();
+        assert!((x - 2.).abs() < 1e-9);
+        assert!((y - 3.).abs() < 1e-9);
+        assert!((z + 1.).abs() < 1e-9);
+
+        let matrix = [[0f32, 1., 2.], [1., 0., 3.]];
+        assert_eq!(gaussian_elimination(matrix), Some([3., 2.]));
+    }
+
+    #[test]
+    fn test_gaussian_elimination_singular() {
+        // Second row is a multiple of the first
+        let matrix = [[1., 2., 3., 4.], [2., 4., 6., 8.], [0., 1., 1., 1.]];
+        assert_eq!(gaussian_elimination::<f64, 3, 4>(matrix), None);
+
+        let matrix = [[0f32, 0., 1.], [0., 0., 2.]];
+        assert_eq!(gaussian_elimination(matrix), None);
+    }
+}
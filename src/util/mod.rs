@@ -1,17 +1,33 @@
 mod bit_matrix;
 mod bit_set;
+mod bounds;
+mod cascade;
 mod char_grid;
 pub mod coord;
+mod digits;
+pub mod graph;
+mod grid_problem;
 mod indexer;
+mod input;
+pub mod linalg;
+pub mod parse;
+mod point_in_loop;
 pub mod shortest_path;
+pub mod test;
 mod vec_map;
 mod vec_set;
 mod vec_table;
 
 pub use bit_matrix::*;
 pub use bit_set::*;
+pub use bounds::*;
+pub use cascade::*;
 pub use char_grid::*;
+pub use digits::*;
+pub use grid_problem::*;
 pub use indexer::*;
+pub use input::*;
+pub use point_in_loop::*;
 pub use vec_map::*;
 pub use vec_set::*;
 pub use vec_table::*;
@@ -0,0 +1,81 @@
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, i64, space0, space1};
+use nom::combinator::value;
+use nom::error::Error;
+use nom::multi::separated_list1;
+use nom::sequence::{pair, separated_pair};
+use nom::{IResult, Parser};
+
+/// Parses a comma, optionally followed by spaces.
+fn list_separator(input: &str) -> IResult<&str, ()> {
+    value((), pair(char(','), space0))(input)
+}
+
+/// Parses a comma-separated list of signed integers, e.g. `-2, 1, -2`.
+pub fn signed_int_list(input: &str) -> IResult<&str, Vec<i64>> {
+    separated_list1(list_separator, i64)(input)
+}
+
+/// Parses three comma-separated components with `component`, e.g. `19, 13, 30`.
+pub fn coord3<'a, T, P>(mut component: P) -> impl FnMut(&'a str) -> IResult<&'a str, [T; 3]>
+where
+    P: Parser<&'a str, T, Error<&'a str>>,
+{
+    move |input| {
+        let (input, x) = component.parse(input)?;
+        let (input, _) = list_separator(input)?;
+        let (input, y) = component.parse(input)?;
+        let (input, _) = list_separator(input)?;
+        let (input, z) = component.parse(input)?;
+        Ok((input, [x, y, z]))
+    }
+}
+
+/// Parses a label followed by `: ` and a space-separated list of items, e.g. `jqt: rhn xhk nvd`.
+pub fn labeled_list<'a, L, T, PL, PT>(
+    label: PL,
+    item: PT,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (L, Vec<T>)>
+where
+    PL: Parser<&'a str, L, Error<&'a str>>,
+    PT: Parser<&'a str, T, Error<&'a str>>,
+{
+    separated_pair(label, tag(": "), separated_list1(space1, item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::character::complete::alpha1;
+
+    #[test]
+    fn test_signed_int_list() {
+        assert_eq!(signed_int_list("-2, 1, -2"), Ok(("", vec![-2, 1, -2])));
+        assert_eq!(signed_int_list("7,-8 @"), Ok((" @", vec![7, -8])));
+        assert!(signed_int_list("x").is_err());
+    }
+
+    #[test]
+    fn test_coord3() {
+        // Day 24
+        assert_eq!(
+            coord3(i64)("19, 13, 30 @ -2, 1, -2"),
+            Ok((" @ -2, 1, -2", [19, 13, 30]))
+        );
+        assert_eq!(coord3(i64)("-1,  -1, -2"), Ok(("", [-1, -1, -2])));
+        assert!(coord3(i64)("1, 2").is_err());
+    }
+
+    #[test]
+    fn test_labeled_list() {
+        // Day 25
+        assert_eq!(
+            labeled_list(alpha1, alpha1)("jqt: rhn xhk nvd"),
+            Ok(("", ("jqt", vec!["rhn", "xhk", "nvd"])))
+        );
+        assert_eq!(
+            labeled_list(alpha1, i64)("seeds: 79 14 55 13"),
+            Ok(("", ("seeds", vec![79, 14, 55, 13])))
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use crate::util::coord::Coord;
+
+/// Counts the cells strictly inside a closed loop on a `width` x `height` grid, using ray-casting parity.
+///
+/// Each row is scanned from left to right, toggling between inside and outside whenever the scan crosses a loop cell
+/// that connects upwards (e.g. the `|`, `L` and `J` pipes). This way, a horizontal run along the loop only counts as
+/// a crossing if it enters and leaves in opposite vertical directions.
+pub fn count_interior_cells(
+    width: usize,
+    height: usize,
+    is_on_loop: impl Fn(Coord) -> bool,
+    connects_up: impl Fn(Coord) -> bool,
+) -> usize {
+    let mut interior_cells = 0;
+
+    for y in 0..height {
+        let mut is_inside = false;
+
+        for x in 0..width {
+            let coord = Coord::new(x, y);
+            if !is_on_loop(coord) {
+                if is_inside {
+                    interior_cells += 1;
+                }
+            } else if connects_up(coord) {
+                is_inside = !is_inside;
+            }
+        }
+    }
+
+    interior_cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_interior_cells() {
+        let rows = ["F--7.", "|..|.", "|.FJ.", "|.L-7", "L---J"];
+        let get = |coord: Coord| rows[coord.y].as_bytes()[coord.x];
+
+        let interior_cells = count_interior_cells(
+            5,
+            5,
+            |coord| get(coord) != b'.',
+            |coord| matches!(get(coord), b'|' | b'L' | b'J'),
+        );
+        assert_eq!(interior_cells, 4);
+    }
+}
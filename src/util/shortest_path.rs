@@ -1,4 +1,6 @@
+use num::traits::float::FloatCore;
 use num::{Num, Zero};
+use ordered_float::OrderedFloat;
 
 pub trait Problem {
     type State;
@@ -11,6 +13,15 @@ pub trait Problem {
         state: &Self::State,
     ) -> impl IntoIterator<Item = (Self::State, Self::Cost)>;
     fn heuristic(&self, state: &Self::State) -> Self::Cost;
+
+    /// Maps a state to the representative of its equivalence class, e.g. to collapse symmetric states. `a_star` applies
+    /// this to every source and successor before looking it up or inserting it, so equivalent states are only explored
+    /// once. Equivalent states must have the same cost to reach a target.
+    ///
+    /// Defaults to the identity.
+    fn canonicalize(&self, state: Self::State) -> Self::State {
+        state
+    }
 }
 
 pub trait OpenSet<State, Cost> {
@@ -18,12 +29,241 @@ pub trait OpenSet<State, Cost> {
     fn pop_min(&mut self) -> Option<State>;
 }
 
+/// How an open set orders states with equal estimated cost.
+///
+/// This doesn't affect the cost returned by `a_star` (for an admissible heuristic), but it does affect which states are
+/// expanded first, and thus which of several equally cheap targets is popped first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Pop the least recently inserted state first (breadth-first within a cost).
+    Fifo,
+    /// Pop the most recently inserted state first (depth-first within a cost). This tends to reach a target sooner,
+    /// since recently inserted states are usually closer to it.
+    Lifo,
+}
+
 pub trait CostMap<State, Cost> {
     fn get(&self, state: &State) -> Option<Cost>;
     fn insert(&mut self, state: State, cost: Cost) -> bool;
 }
 
-pub fn a_star<P, OS, CM>(problem: P, mut open_set: OS, mut cost_map: CM) -> Option<P::Cost>
+/// Wraps a problem to search from a different set of sources, all at zero initial cost.
+///
+/// This is equivalent to adding a virtual super-source with zero-cost edges to each of the given
+/// sources, which lets a single `a_star` run find the cheapest path from any of them.
+pub struct MultiSource<P: Problem> {
+    problem: P,
+    sources: Vec<P::State>,
+}
+
+/// Wraps `problem` so that its sources are replaced by `sources`. See [`MultiSource`].
+pub fn with_super_source<P: Problem>(
+    problem: P,
+    sources: impl IntoIterator<Item = P::State>,
+) -> MultiSource<P> {
+    MultiSource {
+        problem,
+        sources: sources.into_iter().collect(),
+    }
+}
+
+impl<P> Problem for MultiSource<P>
+where
+    P: Problem,
+    P::State: Copy,
+{
+    type State = P::State;
+    type Cost = P::Cost;
+
+    fn sources(&self) -> impl IntoIterator<Item = Self::State> {
+        self.sources.iter().copied()
+    }
+
+    fn is_target(&self, state: &Self::State) -> bool {
+        self.problem.is_target(state)
+    }
+
+    fn successors(
+        &self,
+        state: &Self::State,
+    ) -> impl IntoIterator<Item = (Self::State, Self::Cost)> {
+        self.problem.successors(state)
+    }
+
+    fn heuristic(&self, state: &Self::State) -> Self::Cost {
+        self.problem.heuristic(state)
+    }
+
+    fn canonicalize(&self, state: Self::State) -> Self::State {
+        self.problem.canonicalize(state)
+    }
+}
+
+/// Wraps a problem to search over compact keys instead of its own states, given a bijection between the two.
+///
+/// This lets the open set and cost map be indexed by e.g. a `LinearIndexer` over `0..n`, instead of requiring a
+/// custom indexer for the problem's (richer) states.
+///
+/// The wrapped problem's successors may borrow the decoded state, which only lives for the duration of the call, so
+/// they're collected into a `Vec` on every expansion. This allocation is the main overhead compared to searching over
+/// the original states.
+pub struct Remapped<P, E, D> {
+    problem: P,
+    encode: E,
+    decode: D,
+}
+
+/// Wraps `problem` so that its states are replaced by the keys `encode` maps them to. `decode` must be the inverse of
+/// `encode`. See [`Remapped`].
+pub fn with_remapped_states<P, E, D, K>(problem: P, encode: E, decode: D) -> Remapped<P, E, D>
+where
+    P: Problem,
+    E: Fn(&P::State) -> K,
+    D: Fn(&K) -> P::State,
+{
+    Remapped {
+        problem,
+        encode,
+        decode,
+    }
+}
+
+impl<P, E, D, K> Problem for Remapped<P, E, D>
+where
+    P: Problem,
+    E: Fn(&P::State) -> K,
+    D: Fn(&K) -> P::State,
+{
+    type State = K;
+    type Cost = P::Cost;
+
+    fn sources(&self) -> impl IntoIterator<Item = Self::State> {
+        self.problem
+            .sources()
+            .into_iter()
+            .map(|state| (self.encode)(&state))
+    }
+
+    fn is_target(&self, state: &Self::State) -> bool {
+        self.problem.is_target(&(self.decode)(state))
+    }
+
+    fn successors(
+        &self,
+        state: &Self::State,
+    ) -> impl IntoIterator<Item = (Self::State, Self::Cost)> {
+        self.problem
+            .successors(&(self.decode)(state))
+            .into_iter()
+            .map(|(next_state, cost)| ((self.encode)(&next_state), cost))
+            .collect::<Vec<_>>()
+    }
+
+    fn heuristic(&self, state: &Self::State) -> Self::Cost {
+        self.problem.heuristic(&(self.decode)(state))
+    }
+
+    fn canonicalize(&self, state: Self::State) -> Self::State {
+        (self.encode)(&self.problem.canonicalize((self.decode)(&state)))
+    }
+}
+
+/// Finds the cost of the cheapest path from any of the problem's sources to a target state.
+///
+/// Successors are only (re-)inserted into the open set if their cost strictly improves on the
+/// stored cost, so zero-cost edges (including self-edges) cannot cause the search to loop.
+///
+/// In debug builds, panics if the problem has no sources, since that is almost certainly a bug in
+/// the `Problem` implementation rather than a genuinely unreachable target.
+pub fn a_star<P, OS, CM>(problem: P, open_set: OS, cost_map: CM) -> Option<P::Cost>
+where
+    P: Problem,
+    P::State: Copy,
+    P::Cost: Num + Ord + Copy,
+    OS: OpenSet<P::State, P::Cost>,
+    CM: CostMap<P::State, P::Cost>,
+{
+    a_star_impl(problem, open_set, cost_map, |_, _| {})
+}
+
+/// Variant of [`a_star`] that calls `trace` with each state popped from the open set and its cost
+/// (excluding the heuristic), e.g. to render how the search frontier expands.
+#[cfg(feature = "search_trace")]
+pub fn a_star_traced<P, OS, CM>(
+    problem: P,
+    open_set: OS,
+    cost_map: CM,
+    trace: impl FnMut(&P::State, P::Cost),
+) -> Option<P::Cost>
+where
+    P: Problem,
+    P::State: Copy,
+    P::Cost: Num + Ord + Copy,
+    OS: OpenSet<P::State, P::Cost>,
+    CM: CostMap<P::State, P::Cost>,
+{
+    a_star_impl(problem, open_set, cost_map, trace)
+}
+
+/// Wraps a problem with floating point costs, so that its costs are totally ordered. See [`a_star_float`].
+struct OrderedCosts<P>(P);
+
+impl<P> Problem for OrderedCosts<P>
+where
+    P: Problem,
+    P::Cost: FloatCore,
+{
+    type State = P::State;
+    type Cost = OrderedFloat<P::Cost>;
+
+    fn sources(&self) -> impl IntoIterator<Item = Self::State> {
+        self.0.sources()
+    }
+
+    fn is_target(&self, state: &Self::State) -> bool {
+        self.0.is_target(state)
+    }
+
+    fn successors(
+        &self,
+        state: &Self::State,
+    ) -> impl IntoIterator<Item = (Self::State, Self::Cost)> {
+        self.0
+            .successors(state)
+            .into_iter()
+            .map(|(next_state, cost)| (next_state, OrderedFloat(cost)))
+    }
+
+    fn heuristic(&self, state: &Self::State) -> Self::Cost {
+        OrderedFloat(self.0.heuristic(state))
+    }
+
+    fn canonicalize(&self, state: Self::State) -> Self::State {
+        self.0.canonicalize(state)
+    }
+}
+
+/// Variant of [`a_star`] for problems with floating point costs (e.g. Euclidean distances), which are only
+/// `PartialOrd`. Costs are wrapped in [`OrderedFloat`] for the open set and cost map, which orders `NaN` above all
+/// other values.
+pub fn a_star_float<P, OS, CM>(problem: P, open_set: OS, cost_map: CM) -> Option<P::Cost>
+where
+    P: Problem,
+    P::State: Copy,
+    P::Cost: FloatCore,
+    OS: OpenSet<P::State, OrderedFloat<P::Cost>>,
+    CM: CostMap<P::State, OrderedFloat<P::Cost>>,
+{
+    a_star_impl(OrderedCosts(problem), open_set, cost_map, |_, _| {}).map(|cost| cost.0)
+}
+
+#[inline(always)]
+fn a_star_impl<P, OS, CM>(
+    problem: P,
+    mut open_set: OS,
+    mut cost_map: CM,
+    mut trace: impl FnMut(&P::State, P::Cost),
+) -> Option<P::Cost>
 where
     P: Problem,
     P::State: Copy,
@@ -31,15 +271,20 @@ where
     OS: OpenSet<P::State, P::Cost>,
     CM: CostMap<P::State, P::Cost>,
 {
+    let mut num_sources = 0;
     for state in problem.sources() {
+        let state = problem.canonicalize(state);
         let cost = P::Cost::zero();
         let est_cost = cost + problem.heuristic(&state);
         cost_map.insert(state, cost);
         open_set.insert(state, est_cost);
+        num_sources += 1;
     }
+    debug_assert!(num_sources > 0, "problem has no sources");
 
     while let Some(state) = open_set.pop_min() {
         let cost = cost_map.get(&state).unwrap();
+        trace(&state, cost);
 
         if problem.is_target(&state) {
             // Found the target state
@@ -50,7 +295,15 @@ where
             .successors(&state)
             .into_iter()
             .for_each(|(next_state, next_cost)| {
+                let next_state = problem.canonicalize(next_state);
                 let next_cost = (cost + next_cost) as P::Cost;
+                if cost_map
+                    .get(&next_state)
+                    .is_some_and(|prev_cost| prev_cost <= next_cost)
+                {
+                    // Not an improvement, e.g. reached via a zero-cost edge
+                    return;
+                }
                 if cost_map.insert(next_state, next_cost) {
                     let est_next_cost = (next_cost + problem.heuristic(&next_state)) as P::Cost;
                     open_set.insert(next_state, est_next_cost);
@@ -60,3 +313,223 @@ where
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::coord::Coord;
+    use crate::util::test::{grid_from_rows, HashCostMap, HeapOpenSet};
+    use crate::util::{GridProblem, Indexer, KeyFor, VecTable};
+    use std::cell::Cell;
+
+    fn solve<P: Problem<State = u32, Cost = u32>>(problem: P) -> Option<u32> {
+        a_star(problem, HeapOpenSet::new(), HashCostMap::new())
+    }
+
+    /// A path `0 -> 1 -> ... -> target` with unit costs, where every state also has a zero-cost
+    /// self-edge.
+    struct SelfLoopProblem {
+        target: u32,
+    }
+
+    impl Problem for SelfLoopProblem {
+        type State = u32;
+        type Cost = u32;
+
+        fn sources(&self) -> impl IntoIterator<Item = u32> {
+            [0]
+        }
+
+        fn is_target(&self, state: &u32) -> bool {
+            *state == self.target
+        }
+
+        fn successors(&self, state: &u32) -> impl IntoIterator<Item = (u32, u32)> {
+            [(*state, 0), (*state + 1, 1)]
+        }
+
+        fn heuristic(&self, _state: &u32) -> u32 {
+            0
+        }
+    }
+
+    /// An infinite line of states with unit-cost edges between neighbors.
+    struct LineProblem {
+        target: u32,
+    }
+
+    impl Problem for LineProblem {
+        type State = u32;
+        type Cost = u32;
+
+        fn sources(&self) -> impl IntoIterator<Item = u32> {
+            [0]
+        }
+
+        fn is_target(&self, state: &u32) -> bool {
+            *state == self.target
+        }
+
+        fn successors(&self, state: &u32) -> impl IntoIterator<Item = (u32, u32)> {
+            [(state.saturating_sub(1), 1), (*state + 1, 1)]
+        }
+
+        fn heuristic(&self, state: &u32) -> u32 {
+            state.abs_diff(self.target)
+        }
+    }
+
+    #[test]
+    fn test_with_remapped_states() {
+        let costs = VecTable::from_grid_str(&grid_from_rows(&["1991", "1991", "9111"]), |c| {
+            c.to_digit(10).unwrap()
+        })
+        .unwrap();
+        let indexer = *costs.indexer();
+        let problem = || GridProblem::new(costs.clone(), Coord::new(0, 0), Coord::new(3, 0));
+
+        // Coordinates are remapped to their index in the grid
+        let remapped = with_remapped_states(
+            problem(),
+            |coord: &Coord| indexer.index_for(coord) as u32,
+            |&index: &u32| indexer.key_for(index as usize),
+        );
+        let result = a_star(remapped, HeapOpenSet::new(), HashCostMap::new());
+
+        let unwrapped = a_star(problem(), HeapOpenSet::new(), HashCostMap::new());
+        assert_eq!(result, Some(15));
+        assert_eq!(result, unwrapped);
+    }
+
+    /// A cycle of `2 * half` states with unit-cost edges between neighbors, searching from 0 for the opposite state.
+    /// The cycle is symmetric under `state -> 2 * half - state`, which `canonicalize` exploits if `mirrored` is set.
+    struct CycleProblem<'a> {
+        half: u32,
+        mirrored: bool,
+        expansions: &'a Cell<u32>,
+    }
+
+    impl Problem for CycleProblem<'_> {
+        type State = u32;
+        type Cost = u32;
+
+        fn sources(&self) -> impl IntoIterator<Item = u32> {
+            [0]
+        }
+
+        fn is_target(&self, state: &u32) -> bool {
+            *state == self.half
+        }
+
+        fn successors(&self, state: &u32) -> impl IntoIterator<Item = (u32, u32)> {
+            self.expansions.set(self.expansions.get() + 1);
+            let len = 2 * self.half;
+            [((state + 1) % len, 1), ((state + len - 1) % len, 1)]
+        }
+
+        fn heuristic(&self, _state: &u32) -> u32 {
+            0
+        }
+
+        fn canonicalize(&self, state: u32) -> u32 {
+            if self.mirrored {
+                state.min(2 * self.half - state)
+            } else {
+                state
+            }
+        }
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let solve_cycle = |mirrored| {
+            let expansions = Cell::new(0);
+            let problem = CycleProblem {
+                half: 10,
+                mirrored,
+                expansions: &expansions,
+            };
+            let result = solve(problem);
+            (result, expansions.get())
+        };
+
+        let (result, expansions) = solve_cycle(false);
+        let (mirrored_result, mirrored_expansions) = solve_cycle(true);
+        assert_eq!(result, Some(10));
+        assert_eq!(mirrored_result, Some(10));
+        assert_eq!(mirrored_expansions, 10);
+        assert!(mirrored_expansions < expansions);
+    }
+
+    /// The corners 0 to 3 of a square, with edges of cost 1.2 clockwise and 1.3 counter-clockwise, and a diagonal of
+    /// cost 2.5 from 0 to the target 2. The diagonal is slightly more expensive than going around clockwise (2.4).
+    struct SquareProblem;
+
+    impl Problem for SquareProblem {
+        type State = u32;
+        type Cost = f64;
+
+        fn sources(&self) -> impl IntoIterator<Item = u32> {
+            [0]
+        }
+
+        fn is_target(&self, state: &u32) -> bool {
+            *state == 2
+        }
+
+        fn successors(&self, state: &u32) -> impl IntoIterator<Item = (u32, f64)> {
+            let diagonal = (*state == 0).then_some((2, 2.5));
+            [((state + 1) % 4, 1.2), ((state + 3) % 4, 1.3)]
+                .into_iter()
+                .chain(diagonal)
+        }
+
+        fn heuristic(&self, _state: &u32) -> f64 {
+            0.0
+        }
+    }
+
+    #[test]
+    fn test_a_star_float() {
+        let result = a_star_float(SquareProblem, HeapOpenSet::new(), HashCostMap::new());
+        assert_eq!(result, Some(1.2 + 1.2));
+    }
+
+    #[test]
+    fn test_zero_cost_self_edge_terminates() {
+        assert_eq!(solve(SelfLoopProblem { target: 3 }), Some(3));
+    }
+
+    #[test]
+    fn test_with_super_source() {
+        assert_eq!(solve(LineProblem { target: 10 }), Some(10));
+        assert_eq!(
+            solve(with_super_source(LineProblem { target: 10 }, [0, 7, 12])),
+            Some(2)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "problem has no sources")]
+    fn test_no_sources() {
+        solve(with_super_source(LineProblem { target: 10 }, []));
+    }
+
+    #[test]
+    #[cfg(feature = "search_trace")]
+    fn test_a_star_traced() {
+        let mut popped = vec![];
+        let result = a_star_traced(
+            LineProblem { target: 5 },
+            HeapOpenSet::new(),
+            HashCostMap::new(),
+            |&state, cost| popped.push((state, cost)),
+        );
+
+        assert_eq!(result, Some(5));
+        assert!(!popped.is_empty());
+        assert_eq!(popped.first(), Some(&(0, 0)));
+        assert_eq!(popped.last(), Some(&(5, 5)));
+    }
+}
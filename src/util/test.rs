@@ -0,0 +1,96 @@
+//! Helpers for tests. These aren't behind `#[cfg(test)]`, since the solutions' tests link against the library as
+//! built without it.
+
+use crate::util::shortest_path::{CostMap, OpenSet};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// Joins the rows of a grid with newlines (including a trailing newline), like a puzzle input.
+pub fn grid_from_rows(rows: &[&str]) -> String {
+    rows.iter().flat_map(|row| [row, "\n"]).collect()
+}
+
+/// Returns a xorshift pseudo-random number generator with a fixed seed, so tests are reproducible. Calling it with `n`
+/// returns a number in `0..n`.
+pub fn seeded_random() -> impl FnMut(u64) -> usize {
+    let mut seed = 0x2545f4914f6cdd1du64;
+    move |n| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % n) as usize
+    }
+}
+
+/// Open set backed by a binary heap, without any visited-tracking, so it relies entirely on `a_star` to terminate.
+pub struct HeapOpenSet<S, C>(BinaryHeap<Reverse<(C, S)>>);
+
+impl<S: Ord, C: Ord> HeapOpenSet<S, C> {
+    pub fn new() -> Self {
+        Self(BinaryHeap::new())
+    }
+}
+
+impl<S: Ord, C: Ord> Default for HeapOpenSet<S, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Ord, C: Ord> OpenSet<S, C> for HeapOpenSet<S, C> {
+    fn insert(&mut self, state: S, cost: C) {
+        self.0.push(Reverse((cost, state)));
+    }
+
+    fn pop_min(&mut self) -> Option<S> {
+        self.0.pop().map(|Reverse((_, state))| state)
+    }
+}
+
+/// Cost map backed by a hash map, which accepts every insertion, even if it doesn't improve the stored cost.
+pub struct HashCostMap<S, C>(HashMap<S, C>);
+
+impl<S: Hash + Eq, C: Copy> HashCostMap<S, C> {
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<S: Hash + Eq, C: Copy> Default for HashCostMap<S, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Hash + Eq, C: Copy> CostMap<S, C> for HashCostMap<S, C> {
+    fn get(&self, state: &S) -> Option<C> {
+        self.0.get(state).copied()
+    }
+
+    fn insert(&mut self, state: S, cost: C) -> bool {
+        self.0.insert(state, cost);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_from_rows() {
+        assert_eq!(grid_from_rows(&["#.#", "...", ".#."]), "#.#\n...\n.#.\n");
+        assert_eq!(grid_from_rows(&[]), "");
+    }
+
+    #[test]
+    fn test_seeded_random() {
+        let (mut a, mut b) = (seeded_random(), seeded_random());
+        for n in 1..100 {
+            let value = a(n);
+            assert!(value < n as usize);
+            assert_eq!(value, b(n));
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::util::indexer::Indexer;
+use crate::util::indexer::{Indexer, KeyFor};
 use crate::util::vec_table::VecTable;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -36,8 +36,15 @@ where
         self.table.get_mut(key).take()
     }
 
-    pub fn entry(&mut self, key: &K) -> &mut Option<V> {
-        self.table.get_mut(key)
+    /// Returns the entry for the given key, for in-place manipulation.
+    pub fn entry(&mut self, key: &K) -> Entry<'_, V> {
+        Entry(self.table.get_mut(key))
+    }
+
+    /// Returns a mutable reference to the value associated with the given key, inserting the result of `f` first if
+    /// there is none. Shorthand for `entry(key).or_insert_with(f)`.
+    pub fn get_or_insert_with(&mut self, key: &K, f: impl FnOnce() -> V) -> &mut V {
+        self.table.get_mut(key).get_or_insert_with(f)
     }
 
     /// Returns the number of elements in the map.
@@ -53,4 +60,114 @@ where
     pub fn contains_key(&self, key: &K) -> bool {
         self.table.get(key).is_some()
     }
+
+    /// Retains only the entries for which `f` returns `true`.
+    pub fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool)
+    where
+        I: KeyFor<K>,
+    {
+        for (key, slot) in self.table.iter_mut() {
+            if let Some(value) = slot {
+                if !f(&key, value) {
+                    *slot = None;
+                }
+            }
+        }
+    }
+}
+
+/// A view into a single slot of a [`VecMap`], which may be vacant or occupied.
+pub struct Entry<'a, V>(&'a mut Option<V>);
+
+impl<'a, V> Entry<'a, V> {
+    /// Returns a reference to the value in the entry, if it is occupied.
+    pub fn get(&self) -> Option<&V> {
+        self.0.as_ref()
+    }
+
+    /// Sets the value of the entry and returns a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.0.insert(value)
+    }
+
+    /// Ensures a value is in the entry by inserting `default` if empty, and returns a mutable reference to it.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.0.get_or_insert(default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `f` if empty, and returns a mutable reference to it.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> &'a mut V {
+        self.0.get_or_insert_with(f)
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if empty, and returns a mutable reference to it.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.0.get_or_insert_with(V::default)
+    }
+
+    /// Modifies the value in the entry, if it is occupied.
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        if let Some(value) = self.0.as_mut() {
+            f(value);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::LinearIndexer;
+
+    #[test]
+    fn test_entry_or_default() {
+        let mut map = VecMap::<u32, Vec<u32>, _>::new(LinearIndexer::new(4));
+
+        map.entry(&1).or_default().push(10);
+        assert_eq!(map.get(&1), Some(&vec![10]));
+
+        map.entry(&1).or_default().push(20);
+        assert_eq!(map.get(&1), Some(&vec![10, 20]));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut map = VecMap::<u32, u32, _>::new(LinearIndexer::new(8));
+        for key in [0, 2, 3, 5, 6] {
+            map.insert(&key, key * 3);
+        }
+
+        map.retain(|_, value| *value % 2 == 0);
+        assert_eq!(map.len(), 3);
+        assert!(map.contains_key(&0));
+        assert!(map.contains_key(&2));
+        assert!(!map.contains_key(&3));
+        assert!(!map.contains_key(&5));
+        assert!(map.contains_key(&6));
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut map = VecMap::<u32, u32, _>::new(LinearIndexer::new(4));
+        let mut calls = 0;
+
+        let value = map.get_or_insert_with(&2, || {
+            calls += 1;
+            42
+        });
+        assert_eq!(*value, 42);
+        *value += 1;
+
+        let value = map.get_or_insert_with(&2, || {
+            calls += 1;
+            0
+        });
+        assert_eq!(*value, 43);
+        assert_eq!(calls, 1);
+        assert_eq!(map.len(), 1);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::util::coord::{DirectedCoord, DirectedCoordIndexer};
 use crate::util::indexer::Indexer;
 use crate::util::vec_map::VecMap;
 
@@ -41,3 +42,33 @@ where
         self.map.is_empty()
     }
 }
+
+impl<T> VecSet<DirectedCoord<T>, DirectedCoordIndexer<T>>
+where
+    DirectedCoordIndexer<T>: Indexer<DirectedCoord<T>>,
+{
+    /// Creates an empty set of directed coordinates in a `width` x `height` grid, e.g. to track visited beams.
+    pub fn directed(width: T, height: T) -> Self {
+        Self::new(DirectedCoordIndexer::new(width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::coord::Direction;
+
+    #[test]
+    fn test_directed() {
+        let mut visited = VecSet::<DirectedCoord<u32>, _>::directed(3, 2);
+        assert!(visited.is_empty());
+
+        assert!(visited.insert(DirectedCoord::new(2, 1, Direction::Left)));
+        assert!(!visited.insert(DirectedCoord::new(2, 1, Direction::Left)));
+
+        assert!(visited.contains(&DirectedCoord::new(2, 1, Direction::Left)));
+        assert!(!visited.contains(&DirectedCoord::new(2, 1, Direction::Right)));
+        assert!(!visited.contains(&DirectedCoord::new(1, 1, Direction::Left)));
+        assert_eq!(visited.len(), 1);
+    }
+}
@@ -1,6 +1,12 @@
+use crate::util::coord::{Coord, CoordIndexer};
 use crate::util::indexer::Indexer;
 use crate::util::KeyFor;
+use itertools::Itertools;
+use num::PrimInt;
 use std::borrow::{Borrow, BorrowMut};
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
 use std::marker::PhantomData;
 use std::ops::{Index, IndexMut};
 
@@ -63,6 +69,74 @@ where
     }
 }
 
+/// Error returned by [`VecTable::from_grid_str`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A line (1-based) doesn't have the same width as the first line.
+    RaggedLine {
+        line: usize,
+        width: usize,
+        expected_width: usize,
+    },
+    /// The grid's width or height doesn't fit in the coordinate type.
+    TooLarge,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::RaggedLine {
+                line,
+                width,
+                expected_width,
+            } => write!(
+                f,
+                "line {} has width {}, expected {}",
+                line, width, expected_width
+            ),
+            ParseError::TooLarge => write!(f, "grid is too large for the coordinate type"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl<T, V> VecTable<Coord<T>, V, CoordIndexer<T>>
+where
+    T: TryFrom<usize>,
+    CoordIndexer<T>: Indexer<Coord<T>>,
+{
+    /// Parses a rectangular grid of characters, mapping each character to a value with `f`.
+    pub fn from_grid_str(input: &str, mut f: impl FnMut(char) -> V) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut data = Vec::with_capacity(input.len());
+
+        for line in input.lines() {
+            let len_before = data.len();
+            data.extend(line.chars().map(&mut f));
+            let line_width = data.len() - len_before;
+
+            match width {
+                None => width = Some(line_width),
+                Some(expected_width) if expected_width != line_width => {
+                    return Err(ParseError::RaggedLine {
+                        line: height + 1,
+                        width: line_width,
+                        expected_width,
+                    });
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        let width = T::try_from(width.unwrap_or(0)).map_err(|_| ParseError::TooLarge)?;
+        let height = T::try_from(height).map_err(|_| ParseError::TooLarge)?;
+        Ok(Self::from_vec(data, CoordIndexer::new(width, height)))
+    }
+}
+
 impl<K, V, I, D> VecTable<K, V, I, D>
 where
     I: Indexer<K>,
@@ -96,6 +170,60 @@ where
         self.keys().zip(self.values())
     }
 
+    /// Returns `true` if both tables hold the same values, ignoring their indexers.
+    pub fn values_eq<J, E>(&self, other: &VecTable<K, V, J, E>) -> bool
+    where
+        V: PartialEq,
+        E: Borrow<[V]>,
+    {
+        self.data.borrow() == other.data.borrow()
+    }
+
+    /// Counts the number of occurrences of each value in the table.
+    pub fn histogram(&self) -> HashMap<V, usize>
+    where
+        V: Hash + Eq + Clone,
+    {
+        let mut histogram = HashMap::new();
+        for value in self.values() {
+            *histogram.entry(value.clone()).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// Returns the keys whose values differ from those in `other`, along with the values in this table. Applying the
+    /// result to `other` with [`VecTable::apply_patch`] makes it equal to this table.
+    pub fn diff_from<'a, J, E>(
+        &'a self,
+        other: &'a VecTable<K, V, J, E>,
+    ) -> impl Iterator<Item = (K, &'a V)> + 'a
+    where
+        I: KeyFor<K>,
+        V: PartialEq,
+        E: Borrow<[V]>,
+    {
+        assert_eq!(self.indexer.len(), other.data.borrow().len());
+        self.iter()
+            .zip(other.data.borrow())
+            .filter(|((_, value), other_value)| value != other_value)
+            .map(|(entry, _)| entry)
+    }
+
+    /// Combines the values of two same-shaped tables cell by cell with `f`, keeping this table's indexer.
+    pub fn zip<U, W, E>(
+        &self,
+        other: &VecTable<K, U, I, E>,
+        mut f: impl FnMut(&V, &U) -> W,
+    ) -> VecTable<K, W, I>
+    where
+        I: Clone,
+        E: Borrow<[U]>,
+    {
+        assert_eq!(self.indexer.len(), other.indexer.len());
+        let data = self.values().zip(other.values()).map(|(a, b)| f(a, b));
+        VecTable::from_vec(data.collect(), self.indexer.clone())
+    }
+
     pub fn view<J: Indexer<K>>(&self, indexer: J) -> VecTable<K, V, J, &[V]> {
         assert_eq!(self.indexer.len(), indexer.len());
         VecTable {
@@ -106,6 +234,36 @@ where
     }
 }
 
+impl<T, V, D> VecTable<Coord<T>, V, CoordIndexer<T>, D>
+where
+    T: PrimInt,
+    CoordIndexer<T>: Indexer<Coord<T>>,
+    D: Borrow<[V]>,
+{
+    /// Returns a reference to the value at the given coordinate, where the grid is repeated infinitely in all
+    /// directions. Both axes are wrapped into the grid with `rem_euclid`.
+    pub fn get_wrapped(&self, coord: Coord<i32>) -> &V {
+        let width = self.indexer.width.to_i32().unwrap();
+        let height = self.indexer.height.to_i32().unwrap();
+        let x = T::from(coord.x.rem_euclid(width)).unwrap();
+        let y = T::from(coord.y.rem_euclid(height)).unwrap();
+        self.get(&Coord::new(x, y))
+    }
+
+    /// Formats the values laid out as a grid, one line per row with the values separated by spaces.
+    pub fn debug_grid(&self) -> String
+    where
+        V: Debug,
+    {
+        let width = self.indexer.width.to_usize().unwrap();
+        self.data
+            .borrow()
+            .chunks(width.max(1))
+            .map(|row| row.iter().map(|value| format!("{:?}", value)).join(" "))
+            .join("\n")
+    }
+}
+
 impl<K, V, I, D> VecTable<K, V, I, D>
 where
     I: Indexer<K>,
@@ -125,11 +283,25 @@ where
         self.data.borrow_mut().iter_mut()
     }
 
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (K, &mut V)>
+    where
+        I: KeyFor<K>,
+    {
+        self.indexer.iter().zip(self.data.borrow_mut().iter_mut())
+    }
+
     /// Inserts the given value at the given key and returns the previous value.
     pub fn insert(&mut self, key: &K, value: V) -> V {
         std::mem::replace(self.get_mut(key), value)
     }
 
+    /// Sets the value of each given key, leaving all other cells unchanged.
+    pub fn apply_patch(&mut self, changes: impl IntoIterator<Item = (K, V)>) {
+        for (key, value) in changes {
+            *self.get_mut(&key) = value;
+        }
+    }
+
     pub fn view_mut<J: Indexer<K>>(&mut self, indexer: J) -> VecTable<K, V, J, &mut [V]> {
         assert_eq!(self.indexer.len(), indexer.len());
         VecTable {
@@ -140,6 +312,28 @@ where
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<K, V, I, D> VecTable<K, V, I, D>
+where
+    V: Send + Sync,
+    D: Borrow<[V]>,
+{
+    /// Returns a parallel iterator over the values in the table.
+    pub fn par_values(&self) -> rayon::slice::Iter<'_, V> {
+        use rayon::prelude::*;
+        self.data.borrow().par_iter()
+    }
+
+    /// Returns a parallel iterator over mutable references to the values in the table.
+    pub fn par_values_mut(&mut self) -> rayon::slice::IterMut<'_, V>
+    where
+        D: BorrowMut<[V]>,
+    {
+        use rayon::prelude::*;
+        self.data.borrow_mut().par_iter_mut()
+    }
+}
+
 impl<K, V, I, D> VecTable<K, V, I, D> {
     /// Returns a reference to the underlying indexer.
     pub fn indexer(&self) -> &I {
@@ -168,3 +362,146 @@ where
         self.get_mut(&key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::coord::{Transform, TransformedIndexer};
+    use crate::util::test::grid_from_rows;
+
+    #[test]
+    fn test_from_grid_str() {
+        let table =
+            VecTable::<Coord<u32>, char, _>::from_grid_str(&grid_from_rows(&["ab", "cd"]), |c| c)
+                .unwrap();
+        assert_eq!(table.indexer().width, 2);
+        assert_eq!(table.indexer().height, 2);
+        assert_eq!(table[Coord::new(0, 1)], 'c');
+
+        let error = VecTable::<Coord<u32>, char, _>::from_grid_str(
+            &grid_from_rows(&["abc", "abc", "ab"]),
+            |c| c,
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            error,
+            ParseError::RaggedLine {
+                line: 3,
+                width: 2,
+                expected_width: 3
+            }
+        );
+        assert_eq!(error.to_string(), "line 3 has width 2, expected 3");
+    }
+
+    #[test]
+    fn test_values_eq() {
+        let data = vec![1, 2, 3, 4, 5, 6];
+        let table = VecTable::<Coord, u32, _>::from_vec(data.clone(), CoordIndexer::new(3, 2));
+        let other = VecTable::<Coord, u32, _>::from_vec(
+            data,
+            TransformedIndexer::new(CoordIndexer::new(3, 2), Transform::Identity),
+        );
+        assert!(table.values_eq(&other));
+
+        let mut other = table.clone();
+        other[Coord::new(2, 1)] = 0;
+        assert!(!table.values_eq(&other));
+    }
+
+    #[test]
+    fn test_histogram() {
+        let table = VecTable::<Coord<u32>, char, _>::from_grid_str(
+            &grid_from_rows(&["#..#", ".|.#", "...-"]),
+            |c| c,
+        )
+        .unwrap();
+
+        let histogram = table.histogram();
+        assert_eq!(histogram.len(), 4);
+        assert_eq!(histogram[&'.'], 7);
+        assert_eq!(histogram[&'#'], 3);
+        assert_eq!(histogram[&'|'], 1);
+        assert_eq!(histogram[&'-'], 1);
+        assert_eq!(histogram.values().sum::<usize>(), 12);
+    }
+
+    #[test]
+    fn test_get_wrapped() {
+        let table =
+            VecTable::<Coord<u32>, char, _>::from_grid_str(&grid_from_rows(&["abc", "def"]), |c| c)
+                .unwrap();
+
+        assert_eq!(*table.get_wrapped(Coord::new(1, 1)), 'e');
+        assert_eq!(*table.get_wrapped(Coord::new(3 * 1000 + 2, 2 * 500)), 'c');
+        assert_eq!(*table.get_wrapped(Coord::new(-1, -1)), 'f');
+        assert_eq!(*table.get_wrapped(Coord::new(-3001, -2000 + 1)), 'f');
+    }
+
+    #[test]
+    fn test_zip() {
+        let a =
+            VecTable::<Coord, u32, _>::from_vec(vec![1, 2, 3, 4, 5, 6], CoordIndexer::new(3, 2));
+        let b = VecTable::<Coord, u32, _>::from_vec(
+            vec![10, 20, 30, 40, 50, 60],
+            CoordIndexer::new(3, 2),
+        );
+
+        let sum = a.zip(&b, |x, y| x + y);
+        assert_eq!(sum[Coord::new(1, 1)], 55);
+        assert_eq!(sum.to_vec(), [11, 22, 33, 44, 55, 66]);
+
+        let greater = b.zip(&a, |x, y| x > y);
+        assert!(greater.values().all(|&g| g));
+    }
+
+    #[test]
+    fn test_apply_patch() {
+        let original =
+            VecTable::<Coord<u32>, char, _>::from_grid_str(&grid_from_rows(&["...", "..."]), |c| c)
+                .unwrap();
+
+        let mut table = original.clone();
+        table.apply_patch([(Coord::new(2, 0), '#'), (Coord::new(0, 1), 'O')]);
+        assert_eq!(
+            table.diff_from(&original).collect::<Vec<_>>(),
+            [(Coord::new(2, 0), &'#'), (Coord::new(0, 1), &'O')]
+        );
+
+        let mut patched = original.clone();
+        patched.apply_patch(table.diff_from(&original).map(|(key, &value)| (key, value)));
+        assert!(patched.values_eq(&table));
+        assert_eq!(table.diff_from(&patched).count(), 0);
+    }
+
+    #[test]
+    fn test_debug_grid() {
+        let table = VecTable::<Coord, u32, _>::from_vec(vec![1, 2, 30, 4], CoordIndexer::new(2, 2));
+        assert_eq!(table.debug_grid(), "1 2\n30 4");
+
+        let table =
+            VecTable::<Coord<u32>, char, _>::from_grid_str(&grid_from_rows(&["#.", ".#"]), |c| c)
+                .unwrap();
+        assert_eq!(table.debug_grid(), "'#' '.'\n'.' '#'");
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_values() {
+        use rayon::prelude::*;
+
+        let data = (0..1000).collect::<Vec<u32>>();
+        let mut table = VecTable::<Coord, u32, _>::from_vec(data, CoordIndexer::new(100, 10));
+
+        let sequential = table.values().map(|value| value * 3).collect::<Vec<_>>();
+        let parallel = table
+            .par_values()
+            .map(|value| value * 3)
+            .collect::<Vec<_>>();
+        assert_eq!(parallel, sequential);
+
+        table.par_values_mut().for_each(|value| *value *= 3);
+        assert_eq!(table.values().copied().collect::<Vec<_>>(), sequential);
+    }
+}